@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+
+use crate::events::{GameEvent, Item, Source};
+use crate::layout::{self, Line, Region};
+use crate::renderer::Renderer;
+use crate::message_log::ConsoleBuffer;
+use crate::autoplay::Decision;
+use crate::{autoplay, profile};
+use crate::theme::{self, Role};
+use crate::{clear_screen, display_question, select_menu, ClassType, EnemyKind, Player};
+
+pub struct Achievement {
+    pub id: &'static str, // Stored in the profile, so never renamed
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+pub const ALL: [Achievement; 8] = [
+    Achievement { id: "first-victory", name: "First Blood", description: "Defeat your first enemy." },
+    Achievement { id: "seasoned-fighter", name: "Seasoned Fighter", description: "Defeat 25 enemies in a single journey." },
+    Achievement { id: "demon-slayer", name: "Demon Slayer", description: "Defeat the Ancient Demon Lord." },
+    Achievement { id: "unseen-blade", name: "Unseen Blade", description: "Defeat the Ancient Demon Lord as an Assassin." },
+    Achievement { id: "empty-handed", name: "Empty Handed", description: "Defeat the Ancient Demon Lord without buying any gear." },
+    Achievement { id: "heaven-defied", name: "Heaven Defied", description: "Survive a heavenly tribulation." },
+    Achievement { id: "immortal", name: "Immortal", description: "Break through to level 6 and become a Cultivation Immortal." },
+    Achievement { id: "promises-kept", name: "Promises Kept", description: "Complete every quest." },
+];
+
+thread_local! {
+    // Unlock notices waiting to be shown in the message log
+    static NOTICES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+// The achievements an event earns, whether or not they were unlocked before
+fn earned(player: &Player, event: &GameEvent) -> Vec<&'static str> {
+    match *event {
+        GameEvent::EnemyDefeated(EnemyKind::DemonLord) => {
+            let mut earned = vec!["first-victory", "demon-slayer"];
+            if player.class_type == ClassType::Assassin {
+                earned.push("unseen-blade");
+            }
+            if !player.gear_bought {
+                earned.push("empty-handed");
+            }
+            earned
+        }
+        GameEvent::EnemyDefeated(_) => {
+            let mut earned = vec!["first-victory"];
+            if player.enemies_defeated >= 25 {
+                earned.push("seasoned-fighter");
+            }
+            earned
+        }
+        GameEvent::BreakthroughSucceeded { level } if level >= 6 => vec!["immortal"],
+        GameEvent::TribulationSurvived => vec!["heaven-defied"],
+        GameEvent::QuestCompleted { remaining: 0 } => vec!["promises-kept"],
+        _ => Vec::new(),
+    }
+}
+
+// Unlocks what the event earns, writing the profile straight away since the
+// journey can end at any moment
+pub fn on_event(player: &mut Player, _log: &mut ConsoleBuffer, event: &GameEvent) {
+    // Gear from the smith rules out defeating the Ancient Demon Lord empty handed
+    if let GameEvent::ItemAcquired { item: Item::Gear, source: Source::Shop } = event {
+        player.gear_bought = true;
+    }
+    // Achievements are the player's own, not the autoplay bot's
+    if autoplay::active() {
+        return;
+    }
+    // Most events earn nothing, and those shouldn't cost a read of the profile
+    let earned = earned(player, event);
+    if earned.is_empty() {
+        return;
+    }
+    let mut profile = profile::load();
+    let mut notices = Vec::new();
+    for id in earned {
+        if profile.achievements.iter().any(|unlocked| unlocked == id) {
+            continue;
+        }
+        if let Some(achievement) = ALL.iter().find(|achievement| achievement.id == id) {
+            profile.achievements.push(id.to_string());
+            notices.push(format!("Achievement unlocked: {} - {}", achievement.name, achievement.description));
+        }
+    }
+    if notices.is_empty() {
+        return;
+    }
+    if let Err(error) = profile::save(&profile) {
+        notices.push(format!("Your achievements could not be saved: {}", error));
+    }
+    NOTICES.with(|pending| pending.borrow_mut().extend(notices));
+}
+
+// Notices for everything unlocked since the last call
+pub fn take_notices() -> Vec<String> {
+    NOTICES.with(|pending| pending.borrow_mut().drain(..).collect())
+}
+
+// Every achievement, unlocked ones first in the order they were earned
+pub fn view(renderer: &mut Renderer) -> crossterm::Result<()> {
+    let unlocked = profile::load().achievements;
+    let mut achievements: Vec<(&Achievement, bool)> = unlocked
+        .iter()
+        .filter_map(|id| ALL.iter().find(|achievement| achievement.id == id))
+        .map(|achievement| (achievement, true))
+        .collect();
+    let count = achievements.len();
+    achievements.extend(
+        ALL.iter()
+            .filter(|achievement| !unlocked.iter().any(|id| id == achievement.id))
+            .map(|achievement| (achievement, false)),
+    );
+
+    clear_screen(renderer)?;
+    display_question(renderer, &format!("Achievements: {} of {} unlocked", count, ALL.len()))?;
+    let width = layout::rect(renderer, Region::Body)?.map_or(80, |body| body.width as usize);
+    let mut lines = Vec::new();
+    for (achievement, unlocked) in achievements {
+        let (mark, color) = if unlocked { ("[x]", Role::Gold) } else { ("[ ]", Role::System) };
+        lines.push(Line::colored(format!("{} {}", mark, achievement.name), theme::color(color)));
+        lines.extend(
+            layout::wrap(achievement.description, width.saturating_sub(4))
+                .into_iter()
+                .map(|line| Line::plain(format!("    {}", line))),
+        );
+    }
+    layout::show(renderer, Region::Body, lines)?;
+
+    select_menu(renderer, Decision::Leave, &["Back"], &mut 0)?;
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use std::thread::sleep;
+use std::time::Duration;
+use crossterm::style::Color;
+
+use crate::layout::{self, Line, Region, Span};
+use crate::theme::{self, Role};
+use crate::renderer::Renderer;
+use crate::{plain, settings};
+
+const FRAME: Duration = Duration::from_millis(45);
+const FRAMES: usize = 10;
+
+// How fast questions are typed out at the top of the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextSpeed {
+    Slow,
+    Normal,
+    Fast,
+    Instant,
+}
+
+impl TextSpeed {
+    pub const ALL: [TextSpeed; 4] = [TextSpeed::Slow, TextSpeed::Normal, TextSpeed::Fast, TextSpeed::Instant];
+
+    pub fn parse(name: &str) -> Option<TextSpeed> {
+        TextSpeed::ALL.into_iter().find(|speed| speed.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TextSpeed::Slow => "slow",
+            TextSpeed::Normal => "normal",
+            TextSpeed::Fast => "fast",
+            TextSpeed::Instant => "instant",
+        }
+    }
+
+    // Pause after each typed character
+    pub fn delay(self) -> Duration {
+        match self {
+            TextSpeed::Slow => Duration::from_millis(25),
+            TextSpeed::Normal => Duration::from_millis(10),
+            TextSpeed::Fast => Duration::from_millis(4),
+            TextSpeed::Instant => Duration::ZERO,
+        }
+    }
+}
+
+// A short effect played over one of the panels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    DamageNumber { region: Region, amount: i32 }, // Floats up out of the middle of the panel
+    Flash { region: Region },                     // Blinks the panel's health bar
+    Shake { region: Region },                     // Jolts the panel sideways, for heavy blows
+    QiBurst { region: Region },                   // A ring of Qi spreading out from the centre
+}
+
+impl Effect {
+    fn region(self) -> Region {
+        match self {
+            Effect::DamageNumber { region, .. }
+            | Effect::Flash { region }
+            | Effect::Shake { region }
+            | Effect::QiBurst { region } => region,
+        }
+    }
+}
+
+// Animations are skipped when turned off, in plain mode, which has nothing to
+// draw on, and when drawing somewhere other than the terminal, where nobody watches
+pub fn enabled(renderer: &Renderer) -> bool {
+    settings::current().animations && !plain::enabled() && renderer.is_terminal()
+}
+
+// The effects for a hit: the number, a flash of the health bar and a shake when it
+// takes at least a quarter of the target's health
+pub fn hit(region: Region, damage: i32, max_health: i32) -> Vec<Effect> {
+    let mut effects = vec![Effect::DamageNumber { region, amount: damage }, Effect::Flash { region }];
+    if damage * 4 >= max_health {
+        effects.push(Effect::Shake { region });
+    }
+    effects
+}
+
+// Plays the effects together frame by frame over what the regions currently
+// show, then puts the regions back as they were
+pub fn play(renderer: &mut Renderer, effects: &[Effect]) -> crossterm::Result<()> {
+    if !enabled(renderer) || effects.is_empty() {
+        return Ok(());
+    }
+
+    let mut regions: Vec<Region> = Vec::new();
+    for effect in effects {
+        if !regions.contains(&effect.region()) {
+            regions.push(effect.region());
+        }
+    }
+
+    for frame in 0..FRAMES {
+        for &region in &regions {
+            let width = layout::rect(renderer, region)?.map_or(0, |rect| rect.width as usize);
+            let mut lines = layout::contents(region);
+            for effect in effects.iter().filter(|effect| effect.region() == region) {
+                apply(*effect, frame, width, &mut lines);
+            }
+            layout::draw(renderer, region, &lines)?;
+        }
+        renderer.present()?;
+        sleep(FRAME);
+    }
+
+    for &region in &regions {
+        layout::draw(renderer, region, &layout::contents(region))?;
+    }
+    Ok(())
+}
+
+fn apply(effect: Effect, frame: usize, width: usize, lines: &mut [Line]) {
+    let middle_row = lines.len() / 2;
+    let middle_column = width / 2;
+    match effect {
+        Effect::DamageNumber { amount, .. } => {
+            // Rise one row every other frame
+            let text = format!(" -{} ", amount);
+            let row = middle_row.saturating_sub(frame / 2);
+            let column = middle_column.saturating_sub(text.chars().count() / 2);
+            stamp(lines, row, column, &text, theme::color(Role::HealthLow));
+        }
+        Effect::Flash { .. } if frame.is_multiple_of(2) => {
+            // Swap the colors of everything between the panel's borders
+            if let Some(line) = lines.iter_mut().find(|line| line_text(line).contains("Health")) {
+                let inner = line.spans.len().saturating_sub(1).max(1);
+                for span in line.spans[1..inner].iter_mut() {
+                    let fg = span.fg.unwrap_or(Color::White);
+                    span.fg = Some(span.bg.unwrap_or(Color::Black));
+                    span.bg = Some(fg);
+                }
+            }
+        }
+        Effect::Flash { .. } => {}
+        Effect::Shake { .. } => {
+            // Back and forth, settling down towards the end
+            let offset = [2, 0, 1, 0, 2, 0, 1, 0, 0, 0][frame % FRAMES];
+            for line in lines.iter_mut() {
+                line.spans.insert(0, Span::plain(" ".repeat(offset)));
+            }
+        }
+        Effect::QiBurst { .. } => {
+            // Columns are about half as wide as rows are tall, so the ring is stretched sideways
+            let radius = frame as f32 * 0.6;
+            let color = theme::color(if frame < FRAMES / 2 { Role::QiHigh } else { Role::QiMid });
+            for step in 0..24 {
+                let angle = step as f32 * std::f32::consts::TAU / 24.0;
+                let row = middle_row as f32 + radius * angle.sin();
+                let column = middle_column as f32 + radius * 2.0 * angle.cos();
+                if row >= 0.0 && column >= 0.0 && (column as usize) < width {
+                    stamp(lines, row.round() as usize, column.round() as usize, "*", color);
+                }
+            }
+        }
+    }
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+// Writes text over a line starting at the given column, keeping the colors on either side
+fn stamp(lines: &mut [Line], row: usize, column: usize, text: &str, color: Color) {
+    let line = match lines.get_mut(row) {
+        Some(line) => line,
+        None => return,
+    };
+    let length = text.chars().count();
+    let mut spans = Vec::new();
+    let mut position = 0;
+    let mut inserted = false;
+    for span in line.spans.drain(..) {
+        let count = span.text.chars().count();
+        let before: String = span.text.chars().take(column.saturating_sub(position)).collect();
+        let after: String = span.text.chars().skip((column + length).saturating_sub(position)).collect();
+        if !before.is_empty() {
+            spans.push(Span { text: before, ..span.clone() });
+        }
+        // The stamped text goes in once, inside the span it starts in
+        if !inserted && position + count > column {
+            spans.push(Span { text: text.to_string(), fg: Some(color), bg: span.bg, underlined: false });
+            inserted = true;
+        }
+        if !after.is_empty() {
+            spans.push(Span { text: after, ..span });
+        }
+        position += count;
+    }
+    // Lines shorter than the column get padded out to it
+    if !inserted {
+        spans.push(Span::plain(" ".repeat(column - position)));
+        spans.push(Span::colored(text, color));
+    }
+    line.spans = spans;
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::PathBuf;
+use crossterm::style::Color;
+
+use crate::data::{self, Problem};
+use crate::layout::{self, Line, Span};
+use crate::plain;
+
+// Art is plain text in art/<kind>/<name>.txt, named after what it shows in
+// lowercase with dashes, like art/enemies/shadow-assassin.txt
+const ART_DIR: &str = "art";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Enemy,    // Portraits under the enemy panel
+    Location, // Backdrops beside the stats while travelling
+    Event,    // Title screen, meditation, tribulations
+}
+
+impl Kind {
+    const ALL: [Kind; 3] = [Kind::Enemy, Kind::Location, Kind::Event];
+
+    fn folder(self) -> &'static str {
+        match self {
+            Kind::Enemy => "enemies",
+            Kind::Location => "locations",
+            Kind::Event => "events",
+        }
+    }
+
+    // The widest the space this art is drawn in can get
+    fn max_width(self) -> usize {
+        match self {
+            Kind::Enemy | Kind::Location => layout::PANEL_WIDTH as usize,
+            Kind::Event => layout::MIN_WIDTH as usize - 1,
+        }
+    }
+}
+
+// Copies built into the game so the title and meditation still have art when
+// the folder is missing
+const BUILT_IN: [(Kind, &str, &str); 2] = [
+    (Kind::Event, "title", include_str!("../art/events/title.txt")),
+    (Kind::Event, "meditation", include_str!("../art/events/meditation.txt")),
+];
+
+fn file_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("-").to_lowercase()
+}
+
+// The art's lines without the blank ones around it, or None when there is none
+pub fn load(kind: Kind, name: &str) -> Option<Vec<String>> {
+    let name = file_name(name);
+    let path = data::path(ART_DIR).join(kind.folder()).join(format!("{}.txt", name));
+    let contents = fs::read_to_string(path).ok().or_else(|| {
+        BUILT_IN
+            .iter()
+            .find(|(built_in, known, _)| *built_in == kind && *known == name)
+            .map(|(_, _, art)| art.to_string())
+    })?;
+
+    let lines: Vec<String> = contents.lines().map(|line| line.trim_end().to_string()).collect();
+    let first = lines.iter().position(|line| !line.trim().is_empty())?;
+    let last = lines.iter().rposition(|line| !line.trim().is_empty())?;
+    Some(lines[first..=last].to_vec())
+}
+
+// Art cut down to fit the space it's drawn in and centred across it. Missing
+// art, and plain mode which reads the screen out, just leave the space empty.
+pub fn render(kind: Kind, name: &str, width: usize, height: usize, color: Color) -> Vec<Line> {
+    if plain::enabled() {
+        return Vec::new();
+    }
+    let art = match load(kind, name) {
+        Some(art) => art,
+        None => return Vec::new(),
+    };
+
+    let art_width = art.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let indent = " ".repeat(width.saturating_sub(art_width) / 2);
+    art.iter()
+        .take(height)
+        .map(|line| {
+            let clipped: String = line.chars().take(width).collect();
+            Line::new(vec![Span::plain(indent.clone()), Span::colored(clipped, color)])
+        })
+        .collect()
+}
+
+// Checks every file in the art folders. Art has to be readable text with something
+// in it, and art wider than the space it's drawn in gets a warning since it will be cut.
+pub fn validate() -> (usize, Vec<Problem>) {
+    let mut checked = 0;
+    let mut problems = Vec::new();
+    for kind in Kind::ALL {
+        // A missing folder just means the game falls back to no art
+        let entries = match fs::read_dir(data::path(ART_DIR).join(kind.folder())) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            if path.extension().is_none_or(|extension| extension != "txt") {
+                problems.push(Problem::warning(path, "isn't a .txt file, so the game never shows it"));
+                continue;
+            }
+            checked += 1;
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    problems.push(Problem::error(path, format!("can't be read ({})", error)));
+                    continue;
+                }
+            };
+            let widest = contents.lines().map(|line| line.trim_end().chars().count()).max().unwrap_or(0);
+            if contents.trim().is_empty() {
+                problems.push(Problem::error(path, "is empty"));
+            } else if widest > kind.max_width() {
+                problems.push(Problem::warning(
+                    path,
+                    format!("is {} columns wide and will be cut to fit {}", widest, kind.max_width()),
+                ));
+            }
+        }
+    }
+    (checked, problems)
+}
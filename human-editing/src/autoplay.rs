@@ -0,0 +1,310 @@
+use std::cell::RefCell;
+
+use crate::combat::Action;
+use crate::menu::{Destination, Encounter, Gear, QuitChoice, TitleEntry, VillageAction};
+use crate::techniques::{self, Technique};
+use crate::tribulation::{self, Response};
+use crate::{breakthrough_requirements, combat, cultivation, plain, ClassType, Enemy, Player};
+
+// A policy answers every question the game asks in place of the keyboard. The
+// game runs in plain mode while one is playing, so a whole journey can be
+// played headless and read back as text.
+
+// The question being asked, with the state it is about. Menus hand over their
+// entries in the order they are shown, so an answer is a position among them.
+#[derive(Debug, Clone, Copy)]
+pub enum Decision<'a> {
+    Title(&'a [TitleEntry]),
+    Name,
+    Class(&'a [ClassType]),
+    Difficulty(&'a [&'a str]), // Ruleset names
+    Hardcore,
+    Locations(&'a Player, &'a [Destination]),
+    Village(&'a Player, &'a [VillageAction]),
+    TechniqueShop(&'a Player, &'a [&'static Technique]), // Followed by Back
+    GearShop(&'a Player, &'a [Gear]), // Followed by Back
+    Encounter(&'a Player, &'a [Encounter]),
+    Battle(&'a Player, &'a Enemy, &'a [Action]),
+    Breakthrough(&'a Player),
+    Tribulation { player: &'a Player, wave: u32, waves: u32, strike: i32, responses: &'a [Response] },
+    HeartDemon(char), // The key that banishes it
+    Quit(&'a [QuitChoice]),
+    Leave, // Screens that only need leaving, like reports, settings and the study room
+}
+
+impl Decision<'_> {
+    fn player(&self) -> Option<&Player> {
+        match *self {
+            Decision::Locations(player, _)
+            | Decision::Village(player, _)
+            | Decision::TechniqueShop(player, _)
+            | Decision::GearShop(player, _)
+            | Decision::Encounter(player, _)
+            | Decision::Battle(player, _, _)
+            | Decision::Breakthrough(player)
+            | Decision::Tribulation { player, .. } => Some(player),
+            _ => None,
+        }
+    }
+}
+
+// How a policy answers a menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Answer {
+    Choose(usize),
+    Back,
+    Quit,
+}
+
+// Chooses the entry if the menu has it, or backs out
+pub fn pick<T: PartialEq>(entries: &[T], wanted: T) -> Answer {
+    entries.iter().position(|entry| *entry == wanted).map_or(Answer::Back, Answer::Choose)
+}
+
+pub trait PlayerPolicy {
+    fn choose(&mut self, decision: &Decision) -> Answer;
+    fn text(&mut self, decision: &Decision) -> String;
+    fn yes_no(&mut self, decision: &Decision) -> bool;
+}
+
+thread_local! {
+    static POLICY: RefCell<Option<Box<dyn PlayerPolicy>>> = RefCell::new(None);
+}
+
+// Hands the game over to the policy for the rest of the run
+pub fn install(policy: Box<dyn PlayerPolicy>) {
+    plain::enable();
+    POLICY.with(|current| *current.borrow_mut() = Some(policy));
+}
+
+pub(crate) fn active() -> bool {
+    POLICY.with(|policy| policy.borrow().is_some())
+}
+
+fn ask<T>(question: impl FnOnce(&mut dyn PlayerPolicy) -> T) -> Option<T> {
+    POLICY.with(|policy| policy.borrow_mut().as_mut().map(|policy| question(policy.as_mut())))
+}
+
+// The policy's answer to a menu, or None when nobody but the player is playing.
+// Answers are said like typed ones so the run reads as a transcript.
+pub(crate) fn choose(decision: Decision, options: &[&str]) -> Option<Answer> {
+    let answer = ask(|policy| policy.choose(&decision))?;
+    let answer = match answer {
+        Answer::Choose(index) if index >= options.len() => Answer::Back,
+        answer => answer,
+    };
+    plain::say(&match answer {
+        Answer::Choose(index) => format!("> {}", options[index]),
+        Answer::Back => "> Back".to_string(),
+        Answer::Quit => "> Quit".to_string(),
+    });
+    Some(answer)
+}
+
+pub(crate) fn text(decision: Decision, prompt: &str) -> Option<String> {
+    let text = ask(|policy| policy.text(&decision))?;
+    plain::say(&format!("{}{}", prompt, text));
+    Some(text)
+}
+
+pub(crate) fn yes_no(decision: Decision, prompt: &str) -> Option<bool> {
+    let answer = ask(|policy| policy.yes_no(&decision))?;
+    plain::say(&format!("{} {}", prompt, if answer { "Yes" } else { "No" }));
+    Some(answer)
+}
+
+// Gives up on journeys that go on longer than this without an ending, and on
+// days with this many answers, which only happens when stuck in a menu
+const MAX_DAYS: u32 = 3000;
+const MAX_ANSWERS_PER_DAY: u32 = 500;
+// Breaking through to this level is becoming immortal, there is nothing past it
+const IMMORTAL_LEVEL: u32 = 6;
+// Tribulations fled before the bot stops trying and faces the Ancient Demon Lord as it is
+const TRIBULATION_ATTEMPTS: u32 = 3;
+
+// Plays to win: fights in the wilds while they hold ordinary enemies, rests
+// whenever hurt, buys talismans before tribulations and gloves with what is
+// left, trains for every breakthrough up to immortality and then takes on the
+// Ancient Demon Lord. A tribulation it keeps fleeing is left for the fight.
+#[derive(Debug, Default)]
+pub struct HeuristicBot {
+    manuals_checked_at: Option<i32>, // Gold held when the manual shop last had nothing to buy
+    tribulations_fled: u32,
+    day: u32,
+    answers_today: u32,
+}
+
+impl HeuristicBot {
+    pub fn new() -> HeuristicBot {
+        HeuristicBot::default()
+    }
+
+    fn hurt(player: &Player) -> bool {
+        player.health < player.max_health || player.meridian_damage > 0
+    }
+
+    // Qi the next breakthrough needs, i32::MAX at the peak
+    fn breakthrough_qi(player: &Player) -> i32 {
+        let (qi_needed, _) = breakthrough_requirements(player.cultivation_level);
+        i32::try_from(qi_needed).unwrap_or(i32::MAX)
+    }
+
+    fn tribulation_ahead(player: &Player) -> bool {
+        tribulation::is_major(player.cultivation_level + 1)
+    }
+
+    // Whether there is a breakthrough left that the Qi can be gathered for
+    fn can_break_through(&self, player: &Player) -> bool {
+        player.cultivation_level < IMMORTAL_LEVEL
+            && Self::breakthrough_qi(player) <= cultivation::max_qi(player)
+            && (!Self::tribulation_ahead(player) || self.tribulations_fled < TRIBULATION_ATTEMPTS)
+    }
+
+    fn ready_to_break_through(&self, player: &Player) -> bool {
+        self.can_break_through(player) && player.qi >= Self::breakthrough_qi(player)
+    }
+
+    // From level 5 the wilds only hold the Ancient Demon Lord, so training comes first
+    fn training(&self, player: &Player) -> bool {
+        player.cultivation_level >= 5 && self.can_break_through(player)
+    }
+
+    // The gear worth buying now, if any
+    fn wanted_gear(player: &Player) -> Option<Gear> {
+        if Self::tribulation_ahead(player) && player.talismans < 3 && player.gold >= Gear::ThunderWard.price() {
+            Some(Gear::ThunderWard)
+        } else if player.gold >= Gear::IronFistGloves.price() {
+            Some(Gear::IronFistGloves)
+        } else {
+            None
+        }
+    }
+
+    fn village(&self, player: &Player) -> VillageAction {
+        if Self::hurt(player) {
+            VillageAction::Rest
+        } else if Self::wanted_gear(player).is_some() {
+            VillageAction::BuyGear
+        } else if self.manuals_checked_at.is_none_or(|gold| player.gold > gold) && !techniques::unlearned(player).is_empty() {
+            VillageAction::BuyTechniques
+        } else if self.ready_to_break_through(player) {
+            VillageAction::AttemptBreakthrough
+        } else if self.training(player) {
+            VillageAction::TrainQi
+        } else {
+            VillageAction::Back
+        }
+    }
+
+    fn locations(&self, player: &Player) -> Destination {
+        if Self::hurt(player) || self.ready_to_break_through(player) || self.training(player) {
+            Destination::Village
+        } else {
+            Destination::Wilds
+        }
+    }
+
+    // The hardest hitting attack there is Qi for, or running when nearly dead
+    fn battle(player: &Player, enemy: &Enemy, actions: &[Action]) -> Action {
+        if !enemy.is_boss && player.health * 4 < player.max_health && actions.contains(&Action::Flee) {
+            return Action::Flee;
+        }
+        let attack = (player.attack - enemy.defense).max(1);
+        let qi_attack = (combat::qi_attack_power(player) - enemy.defense).max(1);
+        if player.qi >= cultivation::qi_skill_cost(player) && qi_attack > attack {
+            Action::Qi
+        } else {
+            Action::Attack
+        }
+    }
+
+    // Channels Qi through the early waves and keeps the talismans for the
+    // strongest strikes at the end, giving up on the tribulation rather than
+    // dying when neither is left
+    fn tribulation(player: &Player, wave: u32, waves: u32, strike: i32) -> Response {
+        let waves_left = waves - wave + 1;
+        if player.talismans > 0 && (player.talismans >= waves_left || player.qi < strike) {
+            Response::Talisman
+        } else if player.qi >= strike && player.health > strike * 3 / 10 {
+            Response::Channel
+        } else if player.talismans > 0 {
+            Response::Talisman
+        } else if player.health > strike {
+            Response::Brace
+        } else {
+            Response::Abandon
+        }
+    }
+
+    // The first manual suited to the class that can be afforded
+    fn manual(&mut self, player: &Player, manuals: &[&Technique]) -> Answer {
+        let affordable = manuals
+            .iter()
+            .position(|manual| manual.affinity == player.class_type && manual.price <= player.gold);
+        if affordable.is_none() {
+            self.manuals_checked_at = Some(player.gold);
+        }
+        affordable.map_or(Answer::Back, Answer::Choose)
+    }
+}
+
+impl PlayerPolicy for HeuristicBot {
+    fn choose(&mut self, decision: &Decision) -> Answer {
+        if let Some(player) = decision.player() {
+            if player.days != self.day {
+                self.day = player.days;
+                self.answers_today = 0;
+            }
+        }
+        self.answers_today += 1;
+        let giving_up = self.day >= MAX_DAYS || self.answers_today > MAX_ANSWERS_PER_DAY;
+        if let Decision::Quit(choices) = *decision {
+            return match pick(choices, QuitChoice::Quit) {
+                Answer::Back if giving_up => pick(choices, QuitChoice::SaveAndQuit),
+                quit if giving_up => quit,
+                _ => pick(choices, QuitChoice::KeepPlaying),
+            };
+        }
+        if giving_up {
+            return Answer::Quit;
+        }
+
+        match *decision {
+            Decision::Title(entries) => pick(entries, TitleEntry::NewJourney),
+            Decision::Class(classes) => pick(classes, ClassType::MartialArtist),
+            Decision::Difficulty(names) => pick(names, "normal"),
+            Decision::Locations(player, destinations) => pick(destinations, self.locations(player)),
+            Decision::Village(player, actions) => pick(actions, self.village(player)),
+            Decision::TechniqueShop(player, manuals) => self.manual(player, manuals),
+            Decision::GearShop(player, gear) => Self::wanted_gear(player).map_or(Answer::Back, |wanted| pick(gear, wanted)),
+            Decision::Encounter(player, choices) => {
+                pick(choices, if player.health * 2 >= player.max_health { Encounter::Fight } else { Encounter::Run })
+            }
+            Decision::Battle(player, enemy, actions) => pick(actions, Self::battle(player, enemy, actions)),
+            Decision::Tribulation { player, wave, waves, strike, responses } => {
+                let response = Self::tribulation(player, wave, waves, strike);
+                if response == Response::Abandon {
+                    self.tribulations_fled += 1;
+                }
+                pick(responses, response)
+            }
+            // Screens that only need leaving, like reports and the study room
+            _ => Answer::Back,
+        }
+    }
+
+    fn text(&mut self, decision: &Decision) -> String {
+        match *decision {
+            Decision::HeartDemon(key) => key.to_string(),
+            _ => String::from("Bot"),
+        }
+    }
+
+    fn yes_no(&mut self, decision: &Decision) -> bool {
+        // Breakthroughs are only walked into with the Qi for them, and hardcore is declined
+        match *decision {
+            Decision::Breakthrough(player) => player.qi >= Self::breakthrough_qi(player),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use ai_rpg::simulation::{self, Config, Policy};
+
+const USAGE: &str = "\
+Usage: simulate [OPTIONS]
+
+Plays thousands of battles for every class with scripted policies and prints
+win rates, turns taken, health left and gold earned per cultivation level.
+
+Options:
+  --battles <number>     Separate battles per class, policy and level (default 1000)
+  --runs <number>        Runs from level 1 to the boss per class and policy (default 200)
+  --level-battles <n>    Battles a run fights at each level before training for the
+                         breakthrough (default 10)
+  --policy <name>        Only simulate this policy: attack, qi-first or defensive.
+                         Can be given more than once.
+  --difficulty <name>    Difficulty to simulate: a preset or a ruleset file (default normal)
+  --seed <number>        Roll every battle from this seed
+  --csv <path>           Also write the results to a CSV file
+  -h, --help             Show this help";
+
+fn main() {
+    let (config, csv) = match parse(std::env::args().skip(1)) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let rows = match simulation::run(&config) {
+        Ok(rows) => rows,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    };
+    print!("{}", simulation::table(&rows));
+
+    if let Some(path) = csv {
+        if let Err(error) = fs::write(&path, simulation::csv(&rows)) {
+            eprintln!("{} could not be written: {}", path.display(), error);
+            process::exit(1);
+        }
+        println!("Results written to {}", path.display());
+    }
+}
+
+// The simulation to run and where to write the CSV, or None when help was asked for
+fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<(Config, Option<PathBuf>)>, String> {
+    let mut config = Config::default();
+    let mut policies = Vec::new();
+    let mut csv = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| args.next().ok_or_else(|| format!("{} needs a value", option));
+        let count = |option: &str, value: String| {
+            value.parse::<u32>().map_err(|_| format!("`{}` isn't a valid number for {}", value, option))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--battles" => config.battles = count("--battles", value("--battles")?)?,
+            "--runs" => config.runs = count("--runs", value("--runs")?)?,
+            "--level-battles" => config.battles_per_level = count("--level-battles", value("--level-battles")?)?,
+            "--policy" => {
+                let name = value("--policy")?;
+                policies.push(Policy::parse(&name).ok_or_else(|| format!("there is no policy called `{}`", name))?);
+            }
+            "--difficulty" => config.difficulty = Some(value("--difficulty")?),
+            "--seed" => {
+                let seed = value("--seed")?;
+                config.seed = Some(seed.parse().map_err(|_| format!("`{}` isn't a valid seed", seed))?);
+            }
+            "--csv" => csv = Some(PathBuf::from(value("--csv")?)),
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    if !policies.is_empty() {
+        config.policies = policies;
+    }
+    Ok(Some((config, csv)))
+}
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: ai-rpg [COMMAND] [OPTIONS]
+
+Commands:
+  new              Start a new journey without asking about saves
+  continue         Continue the saved journey
+  load <slot>      Continue the journey saved in the given slot
+  validate-data    Check the art, theme and ruleset files, then exit
+
+Options:
+  --seed <number>        Roll every random event from this seed
+  --difficulty <name>    Difficulty for a new journey: story, normal, hard,
+                         ironman or a ruleset from the rulesets folder
+  --plain                Plain text for screen readers, no drawing or animation
+  --autoplay             Let a bot play the journey, printing it as plain text
+  --config <path>        Settings file to use instead of settings.cfg
+  --data-dir <path>      Folder holding the art, themes and rulesets folders
+  -h, --help             Show this help";
+
+// What the game was asked to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Play(Start),
+    ValidateData,
+    Help,
+}
+
+// How a game starts. Without a command the player is asked when there is a save.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Start {
+    Ask,
+    New,
+    Continue,
+    Load(u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub command: Command,
+    pub seed: Option<u64>,
+    pub difficulty: Option<String>,
+    pub plain: bool,
+    pub autoplay: bool,
+    pub config: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+}
+
+// Reads the arguments after the program name. Errors say what was wrong so
+// they can be printed above the usage.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        command: Command::Play(Start::Ask),
+        seed: None,
+        difficulty: None,
+        plain: false,
+        autoplay: false,
+        config: None,
+        data_dir: None,
+    };
+    let mut command: Option<Command> = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| args.next().ok_or_else(|| format!("{} needs a value", option));
+        let parsed = match arg.as_str() {
+            "-h" | "--help" => Some(Command::Help),
+            "--plain" => {
+                options.plain = true;
+                None
+            }
+            "--autoplay" => {
+                options.autoplay = true;
+                None
+            }
+            "--seed" => {
+                let seed = value("--seed")?;
+                options.seed = Some(seed.parse().map_err(|_| format!("`{}` isn't a valid seed", seed))?);
+                None
+            }
+            "--difficulty" => {
+                options.difficulty = Some(value("--difficulty")?);
+                None
+            }
+            "--config" => {
+                options.config = Some(PathBuf::from(value("--config")?));
+                None
+            }
+            "--data-dir" => {
+                options.data_dir = Some(PathBuf::from(value("--data-dir")?));
+                None
+            }
+            "new" => Some(Command::Play(Start::New)),
+            "continue" => Some(Command::Play(Start::Continue)),
+            "load" => {
+                let slot = value("load")?;
+                match slot.parse::<u32>() {
+                    Ok(slot) if slot > 0 => Some(Command::Play(Start::Load(slot))),
+                    _ => return Err(format!("`{}` isn't a save slot, slots are numbered from 1", slot)),
+                }
+            }
+            "validate-data" => Some(Command::ValidateData),
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        };
+
+        if let Some(parsed) = parsed {
+            // Help wins over anything else given with it
+            match &command {
+                Some(Command::Help) => {}
+                Some(_) if parsed != Command::Help => return Err("only one command can be given".to_string()),
+                _ => command = Some(parsed),
+            }
+        }
+    }
+
+    if let Some(command) = command {
+        options.command = command;
+    }
+    Ok(options)
+}
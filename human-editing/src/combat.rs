@@ -0,0 +1,153 @@
+use rand::Rng;
+
+use crate::element::{self, Element};
+use crate::events::{self, GameEvent, Item, Source};
+use crate::message_log::{ConsoleBuffer, MessageKind};
+use crate::techniques::{self, TechniqueKind};
+use crate::{cultivation, random, Enemy, Player};
+
+// The rules of a fight, without anything drawn, so the balance simulator plays
+// exactly the battles the game does
+
+const VICTORY_GOLD: i32 = 20;
+
+// What the player does with their turn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Attack,
+    Qi,
+    Defend,
+    Technique(usize), // Index into the player's learned techniques
+    Flee,
+}
+
+impl Action {
+    // Qi attacks and techniques burst out of the player
+    pub fn uses_qi(self) -> bool {
+        matches!(self, Action::Qi | Action::Technique(_))
+    }
+}
+
+// Plays the player's action, returning true when they got away
+pub fn player_turn(player: &mut Player, enemy: &mut Enemy, action: Action, log: &mut ConsoleBuffer) -> bool {
+    match action {
+        Action::Attack => {
+            let damage = (player.attack - enemy.defense).max(1);
+            log.add_message(MessageKind::Combat, format!("You attack the enemy for {} damage!", damage));
+            enemy.health -= damage;
+        }
+        Action::Qi => {
+            if cultivation::drain_qi(player, cultivation::qi_skill_cost(player)) {
+                let damage = (qi_attack_power(player) - enemy.defense).max(1);
+                let damage = elemental_damage(damage, player.spiritual_root, enemy, log);
+                log.add_message(MessageKind::Combat, format!(
+                    "You unleash a powerful {} Qi attack for {} damage!",
+                    player.spiritual_root.name(),
+                    damage
+                ));
+                enemy.health -= damage;
+            } else {
+                log.add_message(MessageKind::Combat, "Not enough Qi!".to_string());
+            }
+        }
+        Action::Defend => {
+            log.add_message(MessageKind::Combat, "You brace yourself for the enemy's attack!".to_string());
+        }
+        Action::Technique(index) => {
+            let technique = player.techniques[index].technique;
+            if cultivation::drain_qi(player, technique.qi_cost) {
+                let damage = techniques::skill_damage(player, &player.techniques[index], enemy.defense);
+                let skill_element = techniques::skill_element(player, technique);
+                let damage = elemental_damage(damage, skill_element, enemy, log);
+                log.add_message(MessageKind::Combat, format!("You use {} for {} damage!", technique.name, damage));
+                enemy.health -= damage;
+                // Every real fight deepens the understanding of a technique
+                techniques::comprehend_technique(player, index, 3);
+            } else {
+                log.add_message(MessageKind::Combat, "Not enough Qi!".to_string());
+            }
+        }
+        Action::Flee => {
+            if enemy.is_boss {
+                log.add_message(MessageKind::Combat, "There is no escaping the Ancient Demon Lord!".to_string());
+            } else if random::rng().gen_bool(techniques::flee_chance(player)) {
+                techniques::comprehend(player, TechniqueKind::Movement, 5);
+                log.add_message(MessageKind::Combat, format!("You slip away from the {}!", enemy.name));
+                return true;
+            } else {
+                log.add_message(MessageKind::Combat, "You try to flee but the enemy cuts you off!".to_string());
+            }
+        }
+    }
+    false
+}
+
+// The basic Qi attack's damage before defense and elements
+pub fn qi_attack_power(player: &Player) -> i32 {
+    player.cultivation_level as i32 * 15
+}
+
+pub fn enemy_turn(player: &mut Player, enemy: &Enemy, log: &mut ConsoleBuffer) {
+    let mut rng = random::rng();
+    let enemy_action: u32 = rng.gen_range(1..=2);
+    match enemy_action {
+        1 if rng.gen_bool(techniques::evasion_chance(player)) => {
+            techniques::comprehend(player, TechniqueKind::Movement, 2);
+            log.add_message(MessageKind::Combat, "You sidestep the enemy's attack!".to_string());
+        }
+        1 => {
+            let damage = (enemy.attack - player.defense / 2).max(1);
+            let multiplier = element::damage_multiplier(enemy.element, player.spiritual_root, &[]);
+            let damage = element::apply(damage, multiplier);
+            log.add_message(MessageKind::Combat, format!(
+                "The enemy attacks you with {} for {} damage!",
+                enemy.element.name(),
+                damage
+            ));
+            player.health -= damage;
+        }
+        2 => {
+            log.add_message(MessageKind::Combat, "The enemy braces itself!".to_string());
+        }
+        _ => {}
+    }
+}
+
+// Scales Qi damage by the elemental cycle and notes any notable matchup in the log
+fn elemental_damage(damage: i32, attack_element: Element, enemy: &Enemy, log: &mut ConsoleBuffer) -> i32 {
+    let multiplier = element::damage_multiplier(attack_element, enemy.element, &enemy.resistances);
+    if let Some(message) = element::matchup_message(attack_element, enemy.element, multiplier) {
+        log.add_message(MessageKind::Combat, message);
+    }
+    element::apply(damage, multiplier)
+}
+
+// The purse and tallies for winning a fight
+pub fn victory(player: &mut Player, enemy: &Enemy, log: &mut ConsoleBuffer) {
+    player.gold += VICTORY_GOLD;
+    player.enemies_defeated += 1;
+    events::publish(player, log, GameEvent::EnemyDefeated(enemy.kind));
+    events::publish(player, log, GameEvent::GoldChanged(VICTORY_GOLD));
+}
+
+// What is found on a defeated enemy out in the wilds
+pub fn loot(player: &mut Player, enemy: &Enemy, log: &mut ConsoleBuffer) {
+    let mut rng = random::rng();
+    log.add_message(MessageKind::Loot, format!("You defeated the {} and gained {} gold!", enemy.name, VICTORY_GOLD));
+    player.gold += VICTORY_GOLD;
+    events::publish(player, log, GameEvent::GoldChanged(VICTORY_GOLD));
+
+    if rng.gen_bool(0.25) {
+        // Pills are swallowed as soon as they are found
+        player.qi_pills += 1;
+        player.cultivation_speed += 0.05;
+        events::publish(player, log, GameEvent::ItemAcquired { item: Item::QiPill, source: Source::Loot });
+    }
+
+    if rng.gen_bool(0.1) {
+        if let Some(technique) = techniques::random_unlearned(player) {
+            techniques::learn(player, technique);
+            events::publish(player, log, GameEvent::ItemAcquired { item: Item::Manual(technique), source: Source::Loot });
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{techniques, Player};
+
+// Meridians can never be damaged past this point, a little Qi always flows
+const MAX_MERIDIAN_DAMAGE: u32 = 90;
+
+// How much Qi a cultivator of each level can hold with healthy meridians.
+// Always leaves room above the Qi needed for the next breakthrough.
+fn base_qi_capacity(cultivation_level: u32) -> i32 {
+    match cultivation_level {
+        1 => 150,
+        2 => 400,
+        3 => 1200,
+        4 => 2500,
+        5 => 6000,
+        _ => 12000,
+    }
+}
+
+pub fn max_qi(player: &Player) -> i32 {
+    let base = base_qi_capacity(player.cultivation_level);
+    base * (100 - player.meridian_damage as i32) / 100
+}
+
+// Adds Qi up to the player's capacity and returns how much was actually absorbed
+pub fn gain_qi(player: &mut Player, amount: i32) -> i32 {
+    let before = player.qi;
+    player.qi = (player.qi + amount).min(max_qi(player)).max(before);
+    player.qi - before
+}
+
+// Spends Qi on a skill, returns false without spending anything if there isn't enough
+pub fn drain_qi(player: &mut Player, amount: i32) -> bool {
+    if player.qi < amount {
+        return false;
+    }
+    player.qi -= amount;
+    true
+}
+
+// Cost of the basic Qi attack grows with the power poured into it
+pub fn qi_skill_cost(player: &Player) -> i32 {
+    10 * player.cultivation_level as i32
+}
+
+// Qi slowly gathers on its own as time passes, even without meditating
+pub fn regenerate_qi(player: &mut Player) -> i32 {
+    let amount = (2.0 * player.cultivation_level as f32 * techniques::effective_cultivation_speed(player)) as i32;
+    gain_qi(player, amount.max(1))
+}
+
+// Injured meridians shrink capacity, and any Qi above the new limit leaks away
+pub fn injure_meridians(player: &mut Player, percent: u32) {
+    player.meridian_damage = (player.meridian_damage + percent).min(MAX_MERIDIAN_DAMAGE);
+    player.qi = player.qi.min(max_qi(player));
+}
+
+pub fn heal_meridians(player: &mut Player, percent: u32) {
+    player.meridian_damage = player.meridian_damage.saturating_sub(percent);
+}
@@ -0,0 +1,52 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::{art, difficulty, theme};
+
+thread_local! {
+    // Where art, themes and rulesets are read from, the working directory unless --data-dir says otherwise
+    static DIR: RefCell<PathBuf> = RefCell::new(PathBuf::from("."));
+}
+
+pub fn set_dir(dir: PathBuf) {
+    DIR.with(|current| *current.borrow_mut() = dir);
+}
+
+pub fn path(relative: &str) -> PathBuf {
+    DIR.with(|dir| dir.borrow().join(relative))
+}
+
+// Something wrong with a content file. Warnings are files the game can still use.
+pub struct Problem {
+    pub path: PathBuf,
+    pub message: String,
+    pub warning: bool,
+}
+
+impl Problem {
+    pub fn error(path: PathBuf, message: impl Into<String>) -> Self {
+        Problem { path, message: message.into(), warning: false }
+    }
+
+    pub fn warning(path: PathBuf, message: impl Into<String>) -> Self {
+        Problem { path, message: message.into(), warning: true }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = if self.warning { "warning" } else { "error" };
+        write!(f, "{}: {}: {}", level, self.path.display(), self.message)
+    }
+}
+
+// Checks every content file, returning how many were checked and what's wrong with them
+pub fn validate() -> (usize, Vec<Problem>) {
+    let (art_checked, mut problems) = art::validate();
+    let (themes_checked, theme_problems) = theme::validate();
+    let (rulesets_checked, ruleset_problems) = difficulty::validate();
+    problems.extend(theme_problems);
+    problems.extend(ruleset_problems);
+    (art_checked + themes_checked + rulesets_checked, problems)
+}
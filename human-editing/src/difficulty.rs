@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::data::{self, Problem};
+
+const RULESETS_DIR: &str = "rulesets";
+const RULESET_EXTENSION: &str = "rules";
+
+// The balance numbers a journey is played with. Everything but the encounter
+// chance multiplies the game's own numbers, so 1.0 leaves them as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ruleset {
+    pub name: String,
+    pub player_health: f64,
+    pub player_attack: f64,
+    pub player_defense: f64,
+    pub enemy_health: f64,
+    pub enemy_attack: f64,
+    pub enemy_defense: f64,
+    pub breakthrough_chance: f64, // Only minor breakthroughs are rolled, tribulations are fought
+    pub encounter_chance: u32, // Percent of trips into the wilds that meet an enemy
+    pub ironman: bool,         // One save that is written every turn and can't be abandoned, always hardcore
+    pub hardcore: bool,        // The save is deleted when the character dies
+}
+
+const STORY: Ruleset = Ruleset {
+    name: String::new(),
+    player_health: 1.5,
+    player_attack: 1.25,
+    player_defense: 1.25,
+    enemy_health: 0.7,
+    enemy_attack: 0.6,
+    enemy_defense: 0.8,
+    breakthrough_chance: 1.5,
+    encounter_chance: 50,
+    ironman: false,
+    hardcore: false,
+};
+
+const NORMAL: Ruleset = Ruleset {
+    name: String::new(),
+    player_health: 1.0,
+    player_attack: 1.0,
+    player_defense: 1.0,
+    enemy_health: 1.0,
+    enemy_attack: 1.0,
+    enemy_defense: 1.0,
+    breakthrough_chance: 1.0,
+    encounter_chance: 70,
+    ironman: false,
+    hardcore: false,
+};
+
+const HARD: Ruleset = Ruleset {
+    name: String::new(),
+    player_health: 0.85,
+    player_attack: 1.0,
+    player_defense: 1.0,
+    enemy_health: 1.3,
+    enemy_attack: 1.3,
+    enemy_defense: 1.2,
+    breakthrough_chance: 0.8,
+    encounter_chance: 80,
+    ironman: false,
+    hardcore: false,
+};
+
+// Hard, with a single save that can't be walked away from, nor replayed after a death
+const IRONMAN: Ruleset = Ruleset {
+    name: String::new(),
+    player_health: 0.85,
+    player_attack: 1.0,
+    player_defense: 1.0,
+    enemy_health: 1.3,
+    enemy_attack: 1.3,
+    enemy_defense: 1.2,
+    breakthrough_chance: 0.8,
+    encounter_chance: 80,
+    ironman: true,
+    hardcore: true,
+};
+
+pub const PRESETS: [(&str, &str, Ruleset); 4] = [
+    ("story", "Story - for enjoying the journey", STORY),
+    ("normal", "Normal - the journey as intended", NORMAL),
+    ("hard", "Hard - tougher enemies, rarer breakthroughs", HARD),
+    ("ironman", "Ironman - hard, and every step is saved for good", IRONMAN),
+];
+
+thread_local! {
+    // The ruleset of the journey being played
+    static ACTIVE: RefCell<Ruleset> = RefCell::new(normal());
+}
+
+pub fn normal() -> Ruleset {
+    Ruleset { name: "normal".to_string(), ..NORMAL }
+}
+
+pub fn current() -> Ruleset {
+    ACTIVE.with(|active| active.borrow().clone())
+}
+
+pub fn set(ruleset: Ruleset) {
+    ACTIVE.with(|active| *active.borrow_mut() = ruleset);
+}
+
+// Multiplies one of the game's numbers, keeping it at least 1
+pub fn scale(value: i32, multiplier: f64) -> i32 {
+    ((value as f64 * multiplier).round() as i32).max(1)
+}
+
+// A preset, or a ruleset file from the rulesets folder
+pub fn find(name: &str) -> Option<Ruleset> {
+    let name = name.to_lowercase();
+    if let Some((key, _, preset)) = PRESETS.iter().find(|(key, _, _)| *key == name) {
+        return Some(Ruleset { name: key.to_string(), ..preset.clone() });
+    }
+    // Only names listed in the folder are read, so a name can't reach outside it.
+    // Files are named in any case and found in any.
+    let name = custom().into_iter().find(|custom| custom.to_lowercase() == name)?;
+    let contents = fs::read_to_string(ruleset_path(&name)).ok()?;
+    Some(parse(&name, &contents).0)
+}
+
+// Ruleset files in the rulesets folder, by name
+pub fn custom() -> Vec<String> {
+    let mut names: Vec<String> = match fs::read_dir(data::path(RULESETS_DIR)) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == RULESET_EXTENSION))
+            .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .filter(|name| PRESETS.iter().all(|(preset, _, _)| !preset.eq_ignore_ascii_case(name)))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    names
+}
+
+fn ruleset_path(name: &str) -> PathBuf {
+    data::path(RULESETS_DIR).join(format!("{}.{}", name, RULESET_EXTENSION))
+}
+
+// Ruleset files are `key = value` lines on top of an optional `base = <preset>`,
+// which defaults to normal. The same lines are stored in saves. Returns the
+// ruleset and a description of each line that had to be skipped.
+pub fn parse(name: &str, contents: &str) -> (Ruleset, Vec<String>) {
+    let mut ruleset = Ruleset { name: name.to_string(), ..NORMAL };
+    let mut skipped = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => {
+                skipped.push(format!("line {}: expected `key = value`", number + 1));
+                continue;
+            }
+        };
+        if !apply(&mut ruleset, key, value) {
+            skipped.push(format!("line {}: can't use `{} = {}`", number + 1, key, value));
+        }
+    }
+    // An ironman save left behind after a death would replay the fatal turn
+    ruleset.hardcore |= ruleset.ironman;
+    (ruleset, skipped)
+}
+
+// Sets one rule, returning false for unknown keys and values out of range
+pub fn apply(ruleset: &mut Ruleset, key: &str, value: &str) -> bool {
+    let multiplier = value.parse::<f64>().ok().filter(|multiplier| *multiplier > 0.0 && multiplier.is_finite());
+    let field = match key {
+        "base" => {
+            return match PRESETS.iter().find(|(preset, _, _)| *preset == value) {
+                Some((_, _, preset)) => {
+                    *ruleset = Ruleset { name: ruleset.name.clone(), ..preset.clone() };
+                    true
+                }
+                None => false,
+            };
+        }
+        "encounter_chance" => {
+            return match value.parse::<u32>() {
+                Ok(chance) if chance <= 100 => {
+                    ruleset.encounter_chance = chance;
+                    true
+                }
+                _ => false,
+            };
+        }
+        "ironman" => return set_flag(&mut ruleset.ironman, value),
+        "hardcore" => return set_flag(&mut ruleset.hardcore, value),
+        "player_health" => &mut ruleset.player_health,
+        "player_attack" => &mut ruleset.player_attack,
+        "player_defense" => &mut ruleset.player_defense,
+        "enemy_health" => &mut ruleset.enemy_health,
+        "enemy_attack" => &mut ruleset.enemy_attack,
+        "enemy_defense" => &mut ruleset.enemy_defense,
+        "breakthrough_chance" => &mut ruleset.breakthrough_chance,
+        _ => return false,
+    };
+    match multiplier {
+        Some(multiplier) => {
+            *field = multiplier;
+            true
+        }
+        None => false,
+    }
+}
+
+fn set_flag(flag: &mut bool, value: &str) -> bool {
+    match value.parse::<bool>() {
+        Ok(value) => {
+            *flag = value;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+// The ruleset as `key = value` lines that parse back into the same ruleset
+pub fn serialize(ruleset: &Ruleset) -> Vec<(&'static str, String)> {
+    vec![
+        ("player_health", ruleset.player_health.to_string()),
+        ("player_attack", ruleset.player_attack.to_string()),
+        ("player_defense", ruleset.player_defense.to_string()),
+        ("enemy_health", ruleset.enemy_health.to_string()),
+        ("enemy_attack", ruleset.enemy_attack.to_string()),
+        ("enemy_defense", ruleset.enemy_defense.to_string()),
+        ("breakthrough_chance", ruleset.breakthrough_chance.to_string()),
+        ("encounter_chance", ruleset.encounter_chance.to_string()),
+        ("ironman", ruleset.ironman.to_string()),
+        ("hardcore", ruleset.hardcore.to_string()),
+    ]
+}
+
+// Checks every ruleset file, reporting the lines the game would skip
+pub fn validate() -> (usize, Vec<Problem>) {
+    let mut problems = Vec::new();
+    let names = custom();
+    for name in &names {
+        let path = ruleset_path(name);
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                for skipped in parse(name, &contents).1 {
+                    problems.push(Problem::warning(path.clone(), skipped));
+                }
+            }
+            Err(error) => problems.push(Problem::error(path, format!("can't be read ({})", error))),
+        }
+    }
+    (names.len(), problems)
+}
@@ -0,0 +1,124 @@
+use rand::Rng;
+
+use crate::random;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Element {
+    Fire,
+    Water,
+    Wood,
+    Metal,
+    Earth,
+    Lightning, // Mutated wood root
+    Ice,       // Mutated water root
+}
+
+pub const ALL: [Element; 7] = [
+    Element::Fire,
+    Element::Water,
+    Element::Wood,
+    Element::Metal,
+    Element::Earth,
+    Element::Lightning,
+    Element::Ice,
+];
+
+impl Element {
+    pub fn name(self) -> &'static str {
+        match self {
+            Element::Fire => "Fire",
+            Element::Water => "Water",
+            Element::Wood => "Wood",
+            Element::Metal => "Metal",
+            Element::Earth => "Earth",
+            Element::Lightning => "Lightning",
+            Element::Ice => "Ice",
+        }
+    }
+
+    // Mutated roots follow the cycle of the element they grew from
+    fn base(self) -> Element {
+        match self {
+            Element::Lightning => Element::Wood,
+            Element::Ice => Element::Water,
+            element => element,
+        }
+    }
+
+    fn is_variant(self) -> bool {
+        self != self.base()
+    }
+
+    // Generating cycle: wood feeds fire, fire makes earth, earth bears metal,
+    // metal collects water, water nourishes wood
+    fn generates(self) -> Element {
+        match self.base() {
+            Element::Wood => Element::Fire,
+            Element::Fire => Element::Earth,
+            Element::Earth => Element::Metal,
+            Element::Metal => Element::Water,
+            _ => Element::Wood,
+        }
+    }
+
+    // Overcoming cycle: wood parts earth, earth dams water, water quenches fire,
+    // fire melts metal, metal chops wood
+    fn overcomes(self) -> Element {
+        match self.base() {
+            Element::Wood => Element::Earth,
+            Element::Earth => Element::Water,
+            Element::Water => Element::Fire,
+            Element::Fire => Element::Metal,
+            _ => Element::Wood,
+        }
+    }
+}
+
+// Rolls a spiritual root at character creation, mutated roots are rare
+pub fn roll_spiritual_root() -> Element {
+    let mut rng = random::rng();
+    if rng.gen_bool(0.15) {
+        if rng.gen_bool(0.5) { Element::Lightning } else { Element::Ice }
+    } else {
+        ALL[rng.gen_range(0..5)]
+    }
+}
+
+// Damage multiplier for an attack of one element against a target of another
+pub fn damage_multiplier(attack: Element, target: Element, resistances: &[Element]) -> f32 {
+    let mut multiplier = if attack.overcomes() == target.base() {
+        1.5
+    } else if target.overcomes() == attack.base() {
+        0.75
+    } else if attack.generates() == target.base() {
+        0.9 // Feeding the target's own element
+    } else {
+        1.0
+    };
+
+    // Mutated roots strike harder in every matchup
+    if attack.is_variant() {
+        multiplier += 0.1;
+    }
+    if resistances.contains(&attack) {
+        multiplier *= 0.5;
+    }
+    multiplier
+}
+
+pub fn apply(damage: i32, multiplier: f32) -> i32 {
+    ((damage as f32 * multiplier).round() as i32).max(1)
+}
+
+// Short flavour line describing how a matchup went, if it was anything but neutral
+pub fn matchup_message(attack: Element, target: Element, multiplier: f32) -> Option<String> {
+    if multiplier >= 1.4 {
+        Some(format!("{} overcomes {}!", attack.name(), target.name()))
+    } else if multiplier <= 0.6 {
+        Some(format!("{} is resisted!", attack.name()))
+    } else if multiplier < 1.0 {
+        Some(format!("{} is weakened against {}.", attack.name(), target.name()))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+
+use crate::message_log::ConsoleBuffer;
+use crate::techniques::Technique;
+use crate::{EnemyKind, Player};
+
+// Things that happen in a journey. The code where they happen only publishes
+// them; keeping count, unlocking achievements and telling the player is left to
+// whoever subscribed.
+#[derive(Debug, Clone, Copy)]
+pub enum GameEvent {
+    LocationEntered(Location),
+    EnemyDefeated(EnemyKind),
+    BattleFled,
+    DamageDealt(i32),
+    DamageTaken(i32),
+    GoldChanged(i32), // Positive when earned, negative when spent
+    ItemAcquired { item: Item, source: Source },
+    QiTrained(i32),
+    BreakthroughAttempted,
+    BreakthroughSucceeded { level: u32 },
+    BreakthroughFailed,
+    TribulationSurvived,
+    QuestCompleted { remaining: usize }, // Quests still open afterwards
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Location {
+    Wilds,
+    Village,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Item {
+    QiPill,
+    Manual(&'static Technique),
+    Gear, // Anything bought from the village smith
+}
+
+// Where an item came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Loot,
+    Shop,
+    Reward,
+}
+
+// Hears every event published, with the player it happened to and the log to
+// tell them in
+pub type Subscriber = fn(&mut Player, &mut ConsoleBuffer, &GameEvent);
+
+thread_local! {
+    static SUBSCRIBERS: RefCell<Vec<Subscriber>> = const { RefCell::new(Vec::new()) };
+}
+
+// Subscribers hear events in the order they subscribed
+pub fn subscribe(subscriber: Subscriber) {
+    SUBSCRIBERS.with(|subscribers| subscribers.borrow_mut().push(subscriber));
+}
+
+pub fn publish(player: &mut Player, log: &mut ConsoleBuffer, event: GameEvent) {
+    // Copied out so a subscriber can publish events of its own
+    let subscribers = SUBSCRIBERS.with(|subscribers| subscribers.borrow().clone());
+    for subscriber in subscribers {
+        subscriber(player, log, &event);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+
+use crate::layout::{self, Line, Region};
+use crate::renderer::Renderer;
+use crate::theme::{self, Role};
+use crate::autoplay::Decision;
+use crate::{clear_screen, display_question, select_menu};
+
+const GRAVEYARD_FILE: &str = "graveyard.txt";
+
+// A fallen character, remembered across every journey
+pub struct Grave {
+    pub name: String,
+    pub class: String,
+    pub level: u32,
+    pub cause: String,
+    pub enemies_defeated: u32,
+    pub days: u32,
+    pub difficulty: String,
+    pub hardcore: bool,
+}
+
+// Each grave is a block of `key = value` lines, with graves separated by blank lines
+pub fn bury(grave: &Grave) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(GRAVEYARD_FILE)?;
+    writeln!(file, "name = {}", grave.name)?;
+    writeln!(file, "class = {}", grave.class)?;
+    writeln!(file, "level = {}", grave.level)?;
+    writeln!(file, "cause = {}", grave.cause)?;
+    writeln!(file, "enemies_defeated = {}", grave.enemies_defeated)?;
+    writeln!(file, "days = {}", grave.days)?;
+    writeln!(file, "difficulty = {}", grave.difficulty)?;
+    writeln!(file, "hardcore = {}", grave.hardcore)?;
+    writeln!(file)
+}
+
+// Every grave in the order they were dug. Blocks missing a name are skipped and
+// other missing values are left empty, so a hand-edited file still loads.
+pub fn load() -> Vec<Grave> {
+    let contents = fs::read_to_string(GRAVEYARD_FILE).unwrap_or_default();
+    contents
+        .split("\n\n")
+        .filter_map(|block| {
+            let value = |key: &str| {
+                block.lines().find_map(|line| {
+                    let (found, value) = line.split_once('=')?;
+                    (found.trim() == key).then(|| value.trim().to_string())
+                })
+            };
+            let number = |key: &str| value(key).and_then(|value| value.parse().ok()).unwrap_or(0);
+            Some(Grave {
+                name: value("name")?,
+                class: value("class").unwrap_or_default(),
+                level: number("level"),
+                cause: value("cause").unwrap_or_default(),
+                enemies_defeated: number("enemies_defeated"),
+                days: number("days"),
+                difficulty: value("difficulty").unwrap_or_default(),
+                hardcore: value("hardcore").is_some_and(|hardcore| hardcore == "true"),
+            })
+        })
+        .collect()
+}
+
+pub fn is_empty() -> bool {
+    load().is_empty()
+}
+
+// The graveyard screen, newest graves first
+pub fn view(renderer: &mut Renderer) -> crossterm::Result<()> {
+    clear_screen(renderer)?;
+    display_question(renderer, "The graveyard")?;
+
+    let width = layout::rect(renderer, Region::Body)?.map_or(80, |body| body.width as usize);
+    let mut lines = Vec::new();
+    for grave in load().iter().rev() {
+        let title = format!(
+            "{} the {}{}",
+            grave.name,
+            grave.class,
+            if grave.hardcore { " (hardcore)" } else { "" }
+        );
+        lines.push(Line::colored(title, theme::color(Role::Title)));
+        let epitaph = format!(
+            "Reached cultivation level {} on {} difficulty. {}. Defeated {} enemies and survived {} days.",
+            grave.level, grave.difficulty, grave.cause, grave.enemies_defeated, grave.days
+        );
+        lines.extend(layout::wrap(&epitaph, width).into_iter().map(Line::plain));
+        lines.push(Line::default());
+    }
+    layout::show(renderer, Region::Body, lines)?;
+
+    select_menu(renderer, Decision::Leave, &["Back"], &mut 0)?;
+    Ok(())
+}
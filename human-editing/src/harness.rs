@@ -0,0 +1,176 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use crate::renderer::Renderer;
+use crate::{input, random};
+
+// Drives the game's screens without a terminal: key presses come from a script
+// and everything drawn lands on a pretend screen that is read back as text
+
+pub const WIDTH: u16 = 80;
+pub const HEIGHT: u16 = 24;
+
+// Everything the renderer writes, shared with the harness that reads it back
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Harness {
+    pub renderer: Renderer, // For the screens under test to draw with
+    output: Output,
+    rows: Vec<Vec<char>>,
+    column: usize,
+    row: usize,
+}
+
+impl Harness {
+    // Draws into memory, with the same rolls every time
+    pub fn new() -> Harness {
+        let output = Output::default();
+        input::script([]);
+        random::seed(1);
+        Harness {
+            renderer: Renderer::new(Box::new(output.clone()), Some((WIDTH, HEIGHT))),
+            output,
+            rows: vec![vec![' '; WIDTH as usize]; HEIGHT as usize],
+            column: 0,
+            row: 0,
+        }
+    }
+
+    // The keys the next menus and prompts read, replacing any left over
+    pub fn keys(&self, keys: &[KeyCode]) -> &Harness {
+        input::script(keys.iter().map(|code| Event::Key(KeyEvent::new(*code, KeyModifiers::NONE))));
+        self
+    }
+
+    // Terminal events for the next menus and prompts, for keys with modifiers
+    pub fn events(&self, events: impl IntoIterator<Item = Event>) -> &Harness {
+        input::script(events);
+        self
+    }
+
+    // Typed text followed by Enter
+    pub fn typed(&self, text: &str) -> &Harness {
+        let mut keys: Vec<KeyCode> = text.chars().map(KeyCode::Char).collect();
+        keys.push(KeyCode::Enter);
+        self.keys(&keys)
+    }
+
+    // What the screen shows now, one line per row with trailing blanks trimmed
+    pub fn screen(&mut self) -> String {
+        let output = self.written();
+        self.apply(&output);
+        let mut text: String = self
+            .rows
+            .iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string() + "\n")
+            .collect();
+        // Blank rows at the bottom are only noise in a snapshot
+        while text.ends_with("\n\n") {
+            text.pop();
+        }
+        text
+    }
+
+    // Presents what has been drawn and takes what that wrote since last time
+    pub fn written(&mut self) -> String {
+        self.renderer.present().unwrap();
+        String::from_utf8_lossy(&std::mem::take(&mut *self.output.0.borrow_mut())).into_owned()
+    }
+
+    // Plays terminal output onto the screen. Only the escape sequences that move
+    // the cursor or clear cells matter here, colors and the like are skipped.
+    fn apply(&mut self, output: &str) {
+        let mut chars = output.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    let mut parameters = String::new();
+                    let mut command = ' ';
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            command = c;
+                            break;
+                        }
+                        parameters.push(c);
+                    }
+                    self.control(command, &parameters);
+                }
+                '\n' => {
+                    self.row = (self.row + 1).min(HEIGHT as usize - 1);
+                    self.column = 0;
+                }
+                '\r' => self.column = 0,
+                c => {
+                    if self.column < WIDTH as usize {
+                        self.rows[self.row][self.column] = c;
+                    }
+                    self.column += 1;
+                }
+            }
+        }
+    }
+
+    fn control(&mut self, command: char, parameters: &str) {
+        let numbers: Vec<usize> = parameters.split(';').map(|number| number.parse().unwrap_or(0)).collect();
+        let blank = |cells: &mut [char]| cells.iter_mut().for_each(|cell| *cell = ' ');
+        match (command, numbers.first().copied().unwrap_or(0)) {
+            // Positions count from one
+            ('H', _) => {
+                self.row = numbers[0].max(1) - 1;
+                self.column = numbers.get(1).copied().unwrap_or(1).max(1) - 1;
+            }
+            ('G', column) => self.column = column.max(1) - 1,
+            ('J', 2) | ('J', 3) => self.rows.iter_mut().for_each(|row| blank(row)),
+            ('J', _) => {
+                let (row, column) = (self.row, self.column.min(WIDTH as usize));
+                blank(&mut self.rows[row][column..]);
+                self.rows[row + 1..].iter_mut().for_each(|row| blank(row));
+            }
+            ('K', 2) => blank(&mut self.rows[self.row]),
+            ('K', _) => {
+                let column = self.column.min(WIDTH as usize);
+                blank(&mut self.rows[self.row][column..]);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Compares a screen with the one recorded in tests/snapshots. Running the tests
+// with UPDATE_SNAPSHOTS=1 records the screens as they are now instead.
+pub fn assert_snapshot(name: &str, screen: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", &format!("{}.txt", name)].iter().collect();
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, screen).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("there is no snapshot at {}, run with UPDATE_SNAPSHOTS=1 to record it. The screen was:\n{}", path.display(), screen)
+    });
+    assert!(
+        expected == screen,
+        "the screen doesn't match {}\n--- expected\n{}--- actual\n{}",
+        path.display(),
+        expected,
+        screen
+    );
+}
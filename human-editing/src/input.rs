@@ -0,0 +1,239 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+use crossterm::event::{self, Event, KeyCode, MouseButton, MouseEventKind};
+
+use crate::renderer::Renderer;
+use crate::{data, terminal};
+
+const BINDINGS_FILE: &str = "keybindings.cfg";
+
+// What a key press or mouse event means to a menu, independent of the key that was pressed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+    Quit,            // Ctrl+C, which can't be remapped
+    Shortcut(usize), // Number keys pick the matching menu entry directly
+    Click { column: u16, row: u16 },
+}
+
+// Actions players can rebind, by their name in keybindings.cfg
+const REMAPPABLE: [(&str, Action); 6] = [
+    ("up", Action::Up),
+    ("down", Action::Down),
+    ("left", Action::Left),
+    ("right", Action::Right),
+    ("confirm", Action::Confirm),
+    ("back", Action::Back),
+];
+
+thread_local! {
+    static BINDINGS: RefCell<Vec<(KeyCode, Action)>> = RefCell::new(default_bindings());
+    // Events played back in place of the keyboard, when set
+    static SCRIPT: RefCell<Option<VecDeque<Event>>> = const { RefCell::new(None) };
+}
+
+// Arrow keys plus vim keys for moving, Enter or Space to pick, Esc to go back
+fn default_bindings() -> Vec<(KeyCode, Action)> {
+    vec![
+        (KeyCode::Up, Action::Up),
+        (KeyCode::Char('k'), Action::Up),
+        (KeyCode::Down, Action::Down),
+        (KeyCode::Char('j'), Action::Down),
+        (KeyCode::Left, Action::Left),
+        (KeyCode::Char('h'), Action::Left),
+        (KeyCode::Right, Action::Right),
+        (KeyCode::Char('l'), Action::Right),
+        (KeyCode::Enter, Action::Confirm),
+        (KeyCode::Char(' '), Action::Confirm),
+        (KeyCode::Esc, Action::Back),
+    ]
+}
+
+// Kept in the data directory next to the art, themes and rulesets
+pub fn bindings_path() -> PathBuf {
+    data::path(BINDINGS_FILE)
+}
+
+// Loads keybindings.cfg, where each `action = key, key` line replaces the default
+// keys for that action. Without the file the defaults are used as they are.
+pub fn load() {
+    let mut bindings = default_bindings();
+    if let Ok(contents) = fs::read_to_string(bindings_path()) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, keys) = match line.split_once('=') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let action = match REMAPPABLE.iter().find(|(known, _)| *known == name.trim()) {
+                Some((_, action)) => *action,
+                None => continue,
+            };
+            let keys: Vec<KeyCode> = keys.split(',').filter_map(|key| parse_key(key.trim())).collect();
+            if !keys.is_empty() {
+                bindings.retain(|(_, bound)| *bound != action);
+                bindings.extend(keys.into_iter().map(|key| (key, action)));
+            }
+        }
+    }
+    BINDINGS.with(|current| *current.borrow_mut() = bindings);
+}
+
+// Writes out the defaults if there is no keybindings.cfg yet, so players have
+// something to edit. Only done when they go looking for the bindings.
+pub fn write_defaults() -> io::Result<PathBuf> {
+    let path = bindings_path();
+    if !path.exists() {
+        fs::write(&path, defaults_file())?;
+    }
+    Ok(path)
+}
+
+// Each remappable action with the keys bound to it now
+pub fn describe() -> Vec<(&'static str, String)> {
+    REMAPPABLE.iter().map(|(name, action)| (*name, keys(*action).join(", "))).collect()
+}
+
+// The names of the keys bound to an action now
+pub fn keys(action: Action) -> Vec<String> {
+    BINDINGS.with(|bindings| {
+        bindings.borrow().iter().filter(|(_, bound)| *bound == action).map(|(key, _)| key_name(*key)).collect()
+    })
+}
+
+fn defaults_file() -> String {
+    let mut contents = String::from(
+        "# Key bindings: action = key, key\n\
+         # Keys are single characters or one of: up, down, left, right, enter, esc,\n\
+         # space, tab, backspace, pageup, pagedown, home, end\n",
+    );
+    let bindings = default_bindings();
+    for (name, action) in REMAPPABLE {
+        let keys: Vec<String> = bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(key, _)| key_name(*key))
+            .collect();
+        contents.push_str(&format!("{} = {}\n", name, keys.join(", ")));
+    }
+    contents
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let key = match name.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+// Plays these events back in order instead of reading the keyboard from now on
+#[cfg(test)]
+pub fn script(events: impl IntoIterator<Item = Event>) {
+    SCRIPT.with(|script| *script.borrow_mut() = Some(events.into_iter().collect()));
+}
+
+// Whether an event is waiting, like `event::poll`, showing whatever was drawn
+// first. A script that has run out is an error rather than a wait for keys
+// that will never come.
+pub fn poll(renderer: &mut Renderer, timeout: Duration) -> crossterm::Result<bool> {
+    renderer.present()?;
+    match SCRIPT.with(|script| script.borrow().as_ref().map(|events| events.is_empty())) {
+        Some(true) => Err(script_ended()),
+        Some(false) => Ok(true),
+        None => event::poll(timeout),
+    }
+}
+
+// The next event, like `event::read`
+pub fn read(renderer: &mut Renderer) -> crossterm::Result<Event> {
+    renderer.present()?;
+    match SCRIPT.with(|script| script.borrow_mut().as_mut().map(|events| events.pop_front())) {
+        Some(Some(event)) => Ok(event),
+        Some(None) => Err(script_ended()),
+        None => event::read(),
+    }
+}
+
+fn script_ended() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "the scripted input ran out")
+}
+
+// Translates a terminal event into a menu action using the current bindings
+pub fn action(event: &Event) -> Option<Action> {
+    match event {
+        Event::Key(key_event) if terminal::is_interrupt(key_event) => Some(Action::Quit),
+        Event::Key(key_event) => {
+            let bound = BINDINGS.with(|bindings| {
+                bindings
+                    .borrow()
+                    .iter()
+                    .find(|(key, _)| *key == key_event.code)
+                    .map(|(_, action)| *action)
+            });
+            bound.or(match key_event.code {
+                KeyCode::Char(c @ '1'..='9') => Some(Action::Shortcut(c as usize - '1' as usize)),
+                _ => None,
+            })
+        }
+        Event::Mouse(mouse_event) => match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                Some(Action::Click { column: mouse_event.column, row: mouse_event.row })
+            }
+            MouseEventKind::ScrollUp => Some(Action::Up),
+            MouseEventKind::ScrollDown => Some(Action::Down),
+            _ => None,
+        },
+        _ => None,
+    }
+}
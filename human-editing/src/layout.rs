@@ -0,0 +1,290 @@
+use std::cell::RefCell;
+use crossterm::style::Color;
+
+use crate::renderer::{Cell, Renderer};
+use crate::{plain, theme};
+
+// Smallest terminal the game can be drawn in
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 24;
+
+// Width of the stats and enemy panels when there is room to spare
+pub const PANEL_WIDTH: u16 = 36;
+const PANEL_HEIGHT: u16 = 12;
+// Terminals at least this wide get the log beside the panels instead of below them
+const WIDE_WIDTH: u16 = 110;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Region {
+    Header, // Question or title line at the very top
+    Stats,  // Player info panel
+    Enemy,  // Enemy info panel
+    Log,    // Recent messages
+    Menu,   // Bottom area for menus and prompts
+    Body,   // Everything between the header and the menu, for full screen views
+    Screen, // The whole terminal, for the intro and other pages of text
+}
+
+pub struct Layout {
+    pub header: Rect,
+    pub stats: Rect,
+    pub enemy: Rect,
+    pub log: Rect,
+    pub menu: Rect,
+    pub body: Rect,
+    pub screen: Rect,
+}
+
+impl Layout {
+    pub fn rect(&self, region: Region) -> Rect {
+        match region {
+            Region::Header => self.header,
+            Region::Stats => self.stats,
+            Region::Enemy => self.enemy,
+            Region::Log => self.log,
+            Region::Menu => self.menu,
+            Region::Body => self.body,
+            Region::Screen => self.screen,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub text: String,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub underlined: bool,
+}
+
+impl Span {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Span { text: text.into(), fg: None, bg: None, underlined: false }
+    }
+
+    pub fn colored(text: impl Into<String>, fg: Color) -> Self {
+        Span { text: text.into(), fg: Some(fg), bg: None, underlined: false }
+    }
+
+    pub fn on(mut self, bg: Color) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+
+    pub fn underlined(mut self) -> Self {
+        self.underlined = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Line {
+    pub spans: Vec<Span>,
+}
+
+impl Line {
+    pub fn new(spans: Vec<Span>) -> Self {
+        Line { spans }
+    }
+
+    pub fn plain(text: impl Into<String>) -> Self {
+        Line { spans: vec![Span::plain(text)] }
+    }
+
+    pub fn colored(text: impl Into<String>, fg: Color) -> Self {
+        Line { spans: vec![Span::colored(text, fg)] }
+    }
+}
+
+thread_local! {
+    // Everything currently on screen, kept so it can be redrawn after a resize
+    static SCREEN: RefCell<Vec<(Region, Vec<Line>)>> = const { RefCell::new(Vec::new()) };
+}
+
+// Splits the terminal into regions, or None when it is too small to play in
+pub fn compute(width: u16, height: u16) -> Option<Layout> {
+    if width < MIN_WIDTH || height < MIN_HEIGHT {
+        return None;
+    }
+    // Never draw in the last column so full lines can't wrap and scroll the screen
+    let width = width - 1;
+
+    let header = Rect { x: 0, y: 0, width, height: 1 };
+    let menu_height = (height / 3).clamp(8, 12);
+    let menu = Rect { x: 0, y: height - menu_height, width, height: menu_height };
+    let body = Rect { x: 0, y: 1, width, height: height - 1 - menu_height };
+
+    let panel_width = PANEL_WIDTH.min(width / 2);
+    let panel_height = PANEL_HEIGHT.min(body.height - 2);
+    let stats = Rect { x: 0, y: body.y, width: panel_width, height: panel_height };
+    let enemy = Rect { x: panel_width + 1, y: body.y, width: panel_width, height: panel_height };
+
+    // Wide terminals fit the log beside the panels, otherwise it goes underneath
+    let log = if width >= WIDE_WIDTH {
+        let x = enemy.x + enemy.width + 1;
+        Rect { x, y: body.y, width: width - x, height: body.height }
+    } else {
+        let y = body.y + panel_height;
+        Rect { x: 0, y, width, height: body.height - panel_height }
+    };
+
+    let screen = Rect { x: 0, y: 0, width, height };
+
+    Some(Layout { header, stats, enemy, log, menu, body, screen })
+}
+
+pub fn current(renderer: &Renderer) -> crossterm::Result<Option<Layout>> {
+    let (width, height) = renderer.size()?;
+    Ok(compute(width, height))
+}
+
+pub fn rect(renderer: &Renderer, region: Region) -> crossterm::Result<Option<Rect>> {
+    Ok(current(renderer)?.map(|layout| layout.rect(region)))
+}
+
+// Replaces a region's contents and draws it
+pub fn show(renderer: &mut Renderer, region: Region, lines: Vec<Line>) -> crossterm::Result<()> {
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+        screen.retain(|(shown, _)| *shown != region);
+        screen.push((region, lines.clone()));
+    });
+
+    if plain::enabled() {
+        plain::show(region, &lines);
+        return Ok(());
+    }
+    match current(renderer)? {
+        Some(layout) => render(renderer, layout.rect(region), &lines),
+        None => show_too_small(renderer),
+    }
+}
+
+// Draws over a region without remembering it, for animation frames. The
+// retained contents come back with the next show or reflow.
+pub fn draw(renderer: &mut Renderer, region: Region, lines: &[Line]) -> crossterm::Result<()> {
+    match current(renderer)? {
+        Some(layout) => render(renderer, layout.rect(region), lines),
+        None => Ok(()),
+    }
+}
+
+// Records a region's contents without drawing, for text that was already drawn by hand
+pub fn retain(region: Region, lines: Vec<Line>) {
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+        screen.retain(|(shown, _)| *shown != region);
+        screen.push((region, lines));
+    });
+}
+
+// What a region currently shows, so a temporary overlay can put it back
+pub fn contents(region: Region) -> Vec<Line> {
+    SCREEN.with(|screen| {
+        screen
+            .borrow()
+            .iter()
+            .find(|(shown, _)| *shown == region)
+            .map(|(_, lines)| lines.clone())
+            .unwrap_or_default()
+    })
+}
+
+// Wipes the screen and forgets everything that was on it. Nothing is blanked
+// on the terminal itself until the next screen is presented over it.
+pub fn clear(renderer: &mut Renderer) -> crossterm::Result<()> {
+    SCREEN.with(|screen| screen.borrow_mut().clear());
+    if plain::enabled() {
+        return Ok(());
+    }
+    renderer.clear()?;
+    if current(renderer)?.is_none() {
+        show_too_small(renderer)?;
+    }
+    Ok(())
+}
+
+// Redraws everything in its new place, called when the terminal is resized
+pub fn reflow(renderer: &mut Renderer) -> crossterm::Result<()> {
+    // The terminal may have rewrapped or dropped what it showed
+    renderer.invalidate();
+    renderer.clear()?;
+    let layout = match current(renderer)? {
+        Some(layout) => layout,
+        None => return show_too_small(renderer),
+    };
+
+    let regions = SCREEN.with(|screen| screen.borrow().clone());
+    for (region, lines) in &regions {
+        render(renderer, layout.rect(*region), lines)?;
+    }
+    Ok(())
+}
+
+fn show_too_small(renderer: &mut Renderer) -> crossterm::Result<()> {
+    let (width, height) = renderer.size()?;
+    let notice = format!(
+        "Terminal too small ({}x{}). Please resize to at least {}x{}.",
+        width, height, MIN_WIDTH, MIN_HEIGHT
+    );
+    renderer.clear()?;
+    for (row, line) in wrap(&notice, width.saturating_sub(1).max(1) as usize).iter().enumerate() {
+        renderer.put(0, row as u16, line.chars().map(|ch| Cell { ch, ..Cell::BLANK }))?;
+    }
+    Ok(())
+}
+
+fn render(renderer: &mut Renderer, rect: Rect, lines: &[Line]) -> crossterm::Result<()> {
+    for row in 0..rect.height {
+        let mut cells = Vec::with_capacity(rect.width as usize);
+
+        if let Some(line) = lines.get(row as usize) {
+            for span in &line.spans {
+                // Themes pick colors, the terminal decides how many of them it can show
+                let fg = span.fg.and_then(theme::adapt).unwrap_or(Color::Reset);
+                let bg = span.bg.and_then(theme::adapt).unwrap_or(Color::Reset);
+                cells.extend(span.text.chars().map(|ch| Cell { ch, fg, bg, underlined: span.underlined }));
+            }
+        }
+
+        // Blank out whatever was left over from the previous contents
+        cells.resize(rect.width as usize, Cell::BLANK);
+        renderer.put(rect.x, rect.y + row, cells)?;
+    }
+    Ok(())
+}
+
+// Breaks text into lines no wider than the given width, splitting on spaces where possible
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let line_len = line.chars().count();
+            let word_len = word.chars().count();
+            if line_len > 0 && line_len + 1 + word_len > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            // Words longer than a whole line get cut into pieces
+            while line.chars().count() > width {
+                let rest: String = line.chars().skip(width).collect();
+                lines.push(line.chars().take(width).collect());
+                line = rest;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,1644 @@
+mod achievements;
+mod animation;
+mod art;
+mod autoplay;
+mod cli;
+mod combat;
+mod cultivation;
+mod data;
+mod difficulty;
+mod element;
+mod events;
+mod graveyard;
+#[cfg(test)]
+mod harness;
+mod input;
+mod layout;
+mod meditation;
+mod menu;
+mod message_log;
+mod plain;
+mod profile;
+mod quests;
+mod random;
+mod renderer;
+mod save;
+mod settings;
+pub mod simulation;
+mod stats;
+mod techniques;
+mod terminal;
+#[cfg(test)]
+mod tests;
+mod theme;
+mod tribulation;
+mod widgets;
+
+use rand::Rng;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+use animation::{Effect, TextSpeed};
+use difficulty::Ruleset;
+use element::Element;
+use events::{GameEvent, Item, Location, Source};
+use input::Action;
+use layout::{Line, Region, Span};
+use renderer::Renderer;
+use autoplay::Decision;
+use menu::{Destination, Encounter, Gear, MenuStack, QuitChoice, Screen, TitleEntry, VillageAction};
+use message_log::{ConsoleBuffer, MessageKind};
+use techniques::{LearnedTechnique, TechniqueKind};
+use terminal::{RawMode, TerminalGuard};
+use stats::{Report, Stats};
+use theme::Role;
+use tribulation::TribulationOutcome;
+use widgets::GaugeStyle;
+use crossterm::event::{Event, KeyCode};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassType {
+    MartialArtist,
+    QiCultivator,
+    Assassin,
+}
+
+impl ClassType {
+    fn name(self) -> &'static str {
+        match self {
+            ClassType::MartialArtist => "Martial Artist",
+            ClassType::QiCultivator => "Qi Cultivator",
+            ClassType::Assassin => "Assassin",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Player {
+    name: String,
+    class_type: ClassType,
+    health: i32,
+    max_health: i32,
+    attack: i32,
+    defense: i32,
+    qi: i32,
+    cultivation_level: u32,
+    gold: i32,
+    bandits_defeated: u32,
+    cultivation_speed: f32, // Add a field for cultivation speed bonus
+    qi_pills: u32, // Track the number of Qi pills in inventory
+    talismans: u32, // Thunder Ward talismans, burned to negate tribulation lightning
+    meridian_damage: u32, // Percentage of Qi capacity lost to injured meridians
+    techniques: Vec<LearnedTechnique>, // Manuals the player has learned and their comprehension
+    spiritual_root: Element, // Element the player's Qi attacks carry, rolled at creation
+    enemies_defeated: u32,
+    days: u32, // Every action that takes time ends a day
+    gear_bought: bool, // Whether anything was bought from the gear shop this journey
+    stats: Stats,
+}
+
+#[derive(Debug)]
+struct Enemy {
+    name: String,
+    kind: EnemyKind,
+    health: i32,
+    attack: i32,
+    defense: i32,
+    is_boss: bool, // Track if the enemy is the final boss
+    element: Element,
+    resistances: Vec<Element>, // Elements that only deal half damage
+}
+
+// Which of the foes an enemy is, for anything that cares past its name
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EnemyKind {
+    Bandit,
+    RogueCultivator,
+    ShadowAssassin,
+    DemonLord,
+}
+
+impl EnemyKind {
+    fn name(self) -> &'static str {
+        match self {
+            EnemyKind::Bandit => "Bandit",
+            EnemyKind::RogueCultivator => "Rogue Cultivator",
+            EnemyKind::ShadowAssassin => "Shadow Assassin",
+            EnemyKind::DemonLord => "Ancient Demon Lord",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Quest {
+    description: String,
+    goal: quests::Goal,
+    reward: i32,
+    completed: bool,
+}
+
+enum BattleOutcome {
+    Victory,
+    Fled,
+}
+
+#[derive(Debug)]
+struct Npc {
+    name: String,
+    quest: Quest,
+}
+
+// Plays the game with the command line it was started with
+pub fn run() -> crossterm::Result<()> {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Some(dir) = &options.data_dir {
+        data::set_dir(dir.clone());
+    }
+    let start = match options.command {
+        cli::Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        cli::Command::ValidateData => validate_data(),
+        cli::Command::Play(start) => start,
+    };
+    if let Some(seed) = options.seed {
+        random::seed(seed);
+    }
+    let ruleset = options.difficulty.as_ref().map(|name| match difficulty::find(name) {
+        Some(ruleset) => ruleset,
+        None => {
+            eprintln!("There is no difficulty called `{}`.\n\n{}", name, cli::USAGE);
+            std::process::exit(2);
+        }
+    });
+    if ruleset.is_some() && matches!(start, cli::Start::Continue | cli::Start::Load(_)) {
+        eprintln!("Saved journeys keep the difficulty they were started with, ignoring --difficulty.");
+    }
+
+    let config = options.config.unwrap_or_else(|| PathBuf::from(settings::SETTINGS_FILE));
+    settings::load(&config);
+    let preferences = settings::current();
+    theme::apply(&preferences.theme, preferences.color_mode);
+    input::load();
+    // Plain text for screen readers, and whenever the output isn't a terminal to draw on
+    if options.plain || !io::stdout().is_terminal() {
+        plain::enable();
+    }
+    if options.autoplay {
+        autoplay::install(Box::new(autoplay::HeuristicBot::new()));
+    }
+    // Everything that keeps track of the journey hears what happens in it
+    for subscriber in [message_log::on_event, stats::on_event, quests::on_event, achievements::on_event] {
+        events::subscribe(subscriber);
+    }
+
+    // Everyone with a quest to give, in the order they are saved
+    let mut npcs = vec![Npc {
+        name: String::from("Wise Elder"),
+        quest: Quest {
+            description: String::from(
+                "Defeat 3 bandits to prove your worth as a martial artist.",
+            ),
+            goal: quests::Goal::DefeatBandits(3),
+            reward: 100,
+            completed: false,
+        },
+    }];
+
+    // A save asked for on the command line is loaded before touching the terminal,
+    // so a missing or broken one is reported on the normal screen
+    let mut player = None;
+    if let cli::Start::Continue | cli::Start::Load(_) = start {
+        let slot = match start {
+            cli::Start::Load(slot) => slot,
+            _ => save::DEFAULT_SLOT,
+        };
+        match save::load(slot, &mut npcs) {
+            Ok(loaded) => {
+                save::use_slot(slot);
+                player = Some(loaded);
+            }
+            Err(error) => {
+                eprintln!("Save slot {} could not be loaded: {}", slot, error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Enter the alternate screen, the guard puts the terminal back however we exit
+    let _terminal = TerminalGuard::enter()?;
+    // Everything from here on is drawn through this
+    let renderer = &mut Renderer::terminal();
+
+    // Display the game intro
+    display_intro(renderer)?;
+
+    // Continue a saved journey or create a new player
+    let mut console_buffer = ConsoleBuffer::new();
+    if let Some(player) = &player {
+        console_buffer.add_message(MessageKind::System, format!("Welcome back, {}!", player.name));
+    } else if start == cli::Start::Ask {
+        // The title menu, shown when there is more to do than begin a new journey
+        loop {
+            let has_save = save::exists(save::DEFAULT_SLOT);
+            let has_graves = !graveyard::is_empty();
+            if !has_save && !has_graves {
+                break;
+            }
+            let mut entries = Vec::new();
+            if has_save {
+                entries.push(TitleEntry::Continue);
+            }
+            entries.push(TitleEntry::NewJourney);
+            if has_graves {
+                entries.push(TitleEntry::Graveyard);
+            }
+            let title_options: Vec<&str> = entries.iter().map(|entry| entry.name()).collect();
+
+            clear_screen(renderer)?;
+            display_question(renderer, if has_save { "A saved journey was found." } else { "Cultivation Quest" })?;
+            match entries[select_option(renderer, Decision::Title(&entries), &title_options)?] {
+                TitleEntry::Continue => {
+                    match save::load(save::DEFAULT_SLOT, &mut npcs) {
+                        Ok(loaded) => {
+                            console_buffer.add_message(MessageKind::System, format!("Welcome back, {}!", loaded.name));
+                            player = Some(loaded);
+                        }
+                        Err(error) => console_buffer.add_message(
+                            MessageKind::System,
+                            format!("Your save could not be loaded ({}), starting a new journey.", error),
+                        ),
+                    }
+                    break;
+                }
+                TitleEntry::Graveyard => graveyard::view(renderer)?,
+                TitleEntry::NewJourney => break,
+            }
+        }
+    }
+    let mut player = match player {
+        Some(player) => player,
+        None => {
+            let player = create_player(renderer, ruleset);
+            console_buffer.add_message(MessageKind::System, format!(
+                "Welcome, {}! Prepare for your adventure!",
+                player.name
+            ));
+            console_buffer.add_message(MessageKind::System, format!(
+                "Your spiritual root has awakened: {}.",
+                player.spiritual_root.name()
+            ));
+            player
+        }
+    };
+
+    // Game loop variables
+    let mut game_running = true;
+    let mut menus = MenuStack::new(Screen::Locations);
+
+    // Main game loop
+    while game_running {
+        // Quitting from any menu saves the game as it is here
+        if let Err(error) = save::checkpoint(&player, &npcs) {
+            console_buffer.add_message(MessageKind::System, format!("Saving failed: {}", error));
+        }
+
+        // Every turn either changes the player or opens another menu, so redraw
+        clear_screen(renderer)?;
+        display_player_info(renderer, &player)?; // Display updated player stats
+        display_backdrop(renderer, menus.current())?;
+        console_buffer.display(renderer)?;     // Display messages from the buffer
+
+        // Actions report what happened, moving between menus takes no time
+        let outcome = match menus.current() {
+            Screen::Locations => {
+                let location_options = Destination::ALL.map(Destination::name);
+                let choice = menus.select(renderer, Decision::Locations(&player, &Destination::ALL), &location_options)?;
+                match choice.map(|choice| Destination::ALL[choice]) {
+                    Some(Destination::Wilds) => Some(explore_wilds(renderer, &mut player, &mut npcs, &mut console_buffer)?),
+                    Some(Destination::Village) => {
+                        events::publish(&mut player, &mut console_buffer, GameEvent::LocationEntered(Location::Village));
+                        menus.push(Screen::Village);
+                        None
+                    }
+                    Some(Destination::MessageLog) => {
+                        message_log::view_history(renderer, &console_buffer)?;
+                        None
+                    }
+                    Some(Destination::Statistics) => {
+                        stats::view(renderer, "Statistics", &Report::new(&player, "The journey goes on."), "Back")?;
+                        None
+                    }
+                    Some(Destination::Achievements) => {
+                        achievements::view(renderer)?;
+                        None
+                    }
+                    Some(Destination::Theme) => {
+                        choose_theme(renderer)?;
+                        None
+                    }
+                    Some(Destination::Effects) => {
+                        choose_effects(renderer)?;
+                        None
+                    }
+                    Some(Destination::KeyBindings) => {
+                        view_bindings(renderer)?;
+                        None
+                    }
+                    None => {
+                        // Nothing to go back to from here
+                        confirm_quit(renderer)?;
+                        None
+                    }
+                }
+            }
+            Screen::Village => village_actions(renderer, &mut player, &mut game_running, &mut npcs, &mut menus, &mut console_buffer)?,
+            Screen::TechniqueShop => buy_techniques(renderer, &mut player, &mut menus, &mut console_buffer)?,
+            Screen::GearShop => buy_gear(renderer, &mut player, &mut menus, &mut console_buffer)?,
+        };
+        let outcome = match outcome {
+            Some(outcome) => outcome,
+            None => continue,
+        };
+        console_buffer.add_message(MessageKind::System, outcome);
+        for notice in achievements::take_notices() {
+            console_buffer.add_message(MessageKind::Achievement, notice);
+        }
+        player.days += 1;
+
+        // Time passes with every action, letting Qi gather on its own
+        cultivation::regenerate_qi(&mut player);
+    }
+
+    Ok(())
+}
+
+
+// Checks the content files for `validate-data`, failing if any can't be used at all
+fn validate_data() -> ! {
+    let (checked, problems) = data::validate();
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    let errors = problems.iter().filter(|problem| !problem.warning).count();
+    println!("Checked {} files: {} errors, {} warnings.", checked, errors, problems.len() - errors);
+    std::process::exit(if errors > 0 { 1 } else { 0 });
+}
+
+fn display_intro(renderer: &mut Renderer) -> crossterm::Result<()> {
+    // Game instructions
+    let instructions = r#"
+Welcome to Cultivation Quest!
+
+In this game, you play as a martial artist or Qi cultivator on a journey to reach immortality.
+Here are the main mechanics:
+
+1. **Classes**: Choose from different classes with unique attributes.
+2. **Exploration**: Travel through wilds and villages to battle enemies and complete quests.
+3. **Qi and Cultivation**: Train your Qi to reach higher cultivation levels and become more powerful.
+4. **Breakthroughs**: With enough Qi, attempt breakthroughs to ascend to the next level.
+5. **Final Goal**: Reach level 5 and defeat the Ancient Demon Lord. Then, attempt the final breakthrough to level 6 to become an immortal!
+
+**Important**: Each breakthrough has a success chance, which decreases at higher levels. Breaking into levels 3, 5 and 6 calls down a heavenly tribulation: survive the lightning by bracing at the right moment, channeling Qi or burning talismans, or be struck down by the wrath of heaven and earth!
+
+Press Enter to begin your adventure!
+            "#;
+
+    // Plain mode skips the title art and explains how menus are answered instead
+    if plain::enabled() {
+        let mut paragraphs: Vec<&str> = instructions.trim().lines().filter(|line| !line.is_empty()).collect();
+        let last = paragraphs.pop().unwrap_or_default();
+        for paragraph in paragraphs {
+            plain::say(paragraph);
+        }
+        plain::say("Menus are answered by typing the number of a choice. Type b to go back or q to quit.");
+        if !autoplay::active() {
+            plain::read_line(&format!("{} ", last));
+        }
+        return Ok(());
+    }
+
+    let screen = layout::rect(renderer, Region::Screen)?;
+    let width = screen.map_or(80, |screen| screen.width as usize);
+    let mut lines = art::render(art::Kind::Event, "title", width, usize::MAX, theme::color(Role::Title));
+    for paragraph in instructions.trim().lines() {
+        lines.extend(layout::wrap(paragraph, width).into_iter().map(Line::plain));
+    }
+
+    // Always keep the last line telling the player how to continue on screen
+    if let Some(screen) = screen {
+        let height = screen.height as usize;
+        if lines.len() > height {
+            let last = lines.pop().unwrap_or_default();
+            lines.truncate(height - 1);
+            lines.push(last);
+        }
+    }
+    layout::show(renderer, Region::Screen, lines)?;
+
+    // Wait for the player to press Enter
+    let raw_mode = RawMode::enable(renderer)?;
+    loop {
+        let event = input::read(renderer)?;
+        match input::action(&event) {
+            Some(Action::Quit) | Some(Action::Back) => terminal::exit(""),
+            Some(Action::Confirm) | Some(Action::Click { .. }) => break,
+            _ if matches!(event, Event::Resize(_, _)) => layout::reflow(renderer)?,
+            _ => {}
+        }
+    }
+    drop(raw_mode);
+    Ok(())
+}
+
+
+// The difficulty is asked for after the class unless it was given on the command line
+fn create_player(renderer: &mut Renderer, difficulty: Option<Ruleset>) -> Player {
+    clear_screen(renderer).unwrap();
+    // Backing out of the class selection goes back to the name, and out of the
+    // difficulty back to the class
+    let classes = [ClassType::MartialArtist, ClassType::QiCultivator, ClassType::Assassin];
+    let class_options = classes.map(ClassType::name);
+    let mut class_choice = 0;
+    let mut difficulty_choice = 1;
+    let (name, ruleset) = 'name: loop {
+        let name = read_text(renderer, Decision::Name, "Enter your name: ").expect("Failed to read name");
+        while select_menu(renderer, Decision::Class(&classes), &class_options, &mut class_choice).expect("Failed to select option").is_some() {
+            let ruleset = match &difficulty {
+                Some(ruleset) => Some(ruleset.clone()),
+                None => choose_difficulty(renderer, &mut difficulty_choice).expect("Failed to select option"),
+            };
+            if let Some(ruleset) = ruleset {
+                break 'name (name, ruleset);
+            }
+        }
+    };
+    // Hardcore is a choice of its own on top of any difficulty, unless a ruleset file already asks for it
+    let hardcore = ruleset.hardcore
+        || select_yes_no(renderer, Decision::Hardcore, "Play hardcore? Your save is deleted for good when you die.").expect("Failed to select option");
+    difficulty::set(Ruleset { hardcore, ..ruleset });
+
+    new_player(name, classes[class_choice])
+}
+
+// A fresh character of the class, scaled by the active difficulty
+fn new_player(name: String, class_type: ClassType) -> Player {
+    let rules = difficulty::current();
+    let cultivation_level = 5;
+    let (base_health, attack, defense, qi) = match class_type {
+        ClassType::MartialArtist => (120, 18, 12, 5),
+        ClassType::QiCultivator => (80, 8, 6, 25),
+        ClassType::Assassin => (100, 15, 10, 10),
+    };
+    let base_health = difficulty::scale(base_health, rules.player_health);
+    let attack = difficulty::scale(attack, rules.player_attack);
+    let defense = difficulty::scale(defense, rules.player_defense);
+
+    Player {
+        name,
+        cultivation_level,
+        class_type,
+        health: base_health,
+        max_health: base_health,
+        attack,
+        defense,
+        qi,
+        gold: 50,
+        bandits_defeated: 0,
+        cultivation_speed: 1.0,
+        qi_pills: 0,
+        talismans: 0,
+        meridian_damage: 0,
+        techniques: Vec::new(),
+        spiritual_root: element::roll_spiritual_root(),
+        enemies_defeated: 0,
+        days: 0,
+        gear_bought: false,
+        stats: Stats::default(),
+    }
+}
+
+fn choose_difficulty(renderer: &mut Renderer, selected: &mut usize) -> crossterm::Result<Option<Ruleset>> {
+    let custom = difficulty::custom();
+    let mut names: Vec<&str> = difficulty::PRESETS.iter().map(|(name, _, _)| *name).collect();
+    names.extend(custom.iter().map(|name| name.as_str()));
+    let mut options: Vec<String> = difficulty::PRESETS.iter().map(|(_, label, _)| label.to_string()).collect();
+    options.extend(custom.iter().map(|name| format!("Custom - {}", name)));
+    let option_refs: Vec<&str> = options.iter().map(|option| option.as_str()).collect();
+
+    match select_menu(renderer, Decision::Difficulty(&names), &option_refs, selected)? {
+        Some(choice) => Ok(difficulty::find(names[choice])),
+        None => Ok(None),
+    }
+}
+
+// Applies the difficulty's multipliers to a freshly generated enemy
+fn scaled(mut enemy: Enemy) -> Enemy {
+    let rules = difficulty::current();
+    enemy.health = difficulty::scale(enemy.health, rules.enemy_health);
+    enemy.attack = difficulty::scale(enemy.attack, rules.enemy_attack);
+    enemy.defense = difficulty::scale(enemy.defense, rules.enemy_defense);
+    enemy
+}
+
+fn generate_boss() -> Enemy {
+    // Define stats for the final boss, significantly stronger than regular enemies
+    scaled(Enemy {
+        name: EnemyKind::DemonLord.name().to_string(),
+        kind: EnemyKind::DemonLord,
+        health: 1000,
+        attack: 60,
+        defense: 35,
+        is_boss: true,
+        element: Element::Fire,
+        resistances: vec![Element::Fire, Element::Metal],
+    })
+}
+
+fn explore_wilds(
+    renderer: &mut Renderer,
+    player: &mut Player,
+    npcs: &mut [Npc],
+    console_buffer: &mut ConsoleBuffer,
+    ) -> crossterm::Result<String> {
+    let mut rng = random::rng();
+    events::publish(player, console_buffer, GameEvent::LocationEntered(Location::Wilds));
+
+    if player.cultivation_level >= 5 {
+        let mut boss = generate_boss();
+        console_buffer.add_message(
+            MessageKind::Combat,
+            "A powerful aura fills the air... The Ancient Demon Lord appears!".to_string(),
+        );
+        battle(renderer, player, &mut boss, console_buffer)?;
+        if player.health > 0 {
+            // End the game upon defeating the boss
+            terminal::exit("You have defeated the final boss and brought peace to the land!");
+        } else {
+            die(renderer, player, &format!("Slain by the {}", boss.name), "Game Over. You have died.");
+        }
+    } else {
+        // Introduce a random chance to meet an NPC or an enemy
+        let encounter_chance: u32 = rng.gen_range(1..=100);
+        if encounter_chance <= difficulty::current().encounter_chance {
+            // Usually a 70% chance to encounter an enemy, depending on the difficulty
+            let mut enemy = generate_enemy(player);
+            console_buffer.add_message(MessageKind::Combat, format!("A wild {} appears!", enemy.name));
+
+            // Backing out of the encounter is the same as running
+            let encounter_options = Encounter::ALL.map(Encounter::name);
+            let decision = Decision::Encounter(player, &Encounter::ALL);
+            let action_choice = select_menu(renderer, decision, &encounter_options, &mut 0)?.map_or(Encounter::Run, |choice| Encounter::ALL[choice]);
+
+            match action_choice {
+                Encounter::Fight => {
+                    if let BattleOutcome::Fled = battle(renderer, player, &mut enemy, console_buffer)? {
+                        return Ok(format!("You escaped from the {}.", enemy.name));
+                    }
+                    if player.health > 0 {
+                        combat::loot(player, &enemy, console_buffer);
+                        Ok("Battle completed.".to_string())
+                    } else {
+                        die(renderer, player, &format!("Slain by the {}", enemy.name), "Game Over. You have died.");
+                    }
+                }
+                Encounter::Run => Ok("You chose to run away.".to_string()),
+            }
+        } else {
+            // 30% chance to meet an NPC
+            let npc = sought_npc(npcs);
+            console_buffer.add_message(MessageKind::Quest, format!(
+                "You encounter {} in the wilds.",
+                npcs[npc].name
+            ));
+
+            let npc_interaction = talk_to_npc(player, npcs, npc, console_buffer)?;
+            console_buffer.add_message(MessageKind::Quest, npc_interaction);
+
+            Ok("You had an encounter in the wilds.".to_string())
+        }
+    }
+}
+
+fn village_actions(
+    renderer: &mut Renderer,
+    player: &mut Player,
+    game_running: &mut bool,
+    npcs: &mut [Npc],
+    menus: &mut MenuStack,
+    log: &mut ConsoleBuffer,
+    ) -> crossterm::Result<Option<String>> {
+    let actions = VillageAction::ALL.map(VillageAction::name);
+    let action_choice = match menus.select(renderer, Decision::Village(player, &VillageAction::ALL), &actions)? {
+        Some(choice) => VillageAction::ALL[choice],
+        None => return Ok(None),
+    };
+
+    let outcome = match action_choice {
+        VillageAction::Rest => {
+            if rest(player) {
+                format!(
+                    "You rested and recovered health. Your meridians mend ({}% damage remains).",
+                    player.meridian_damage
+                )
+            } else {
+                "You rested and recovered health.".to_string()
+            }
+        }
+        VillageAction::BuyTechniques => {
+            menus.push(Screen::TechniqueShop);
+            return Ok(None);
+        }
+        VillageAction::BuyGear => {
+            menus.push(Screen::GearShop);
+            return Ok(None);
+        }
+        VillageAction::Study => study_techniques(renderer, player)?,
+        VillageAction::TalkToNpc => talk_to_npc(player, npcs, sought_npc(npcs), log)?,
+        VillageAction::TrainQi => train_qi(renderer, player, log)?,
+        VillageAction::AttemptBreakthrough => attempt_breakthrough(renderer, player, log)?,
+        VillageAction::Back => {
+            menus.back();
+            return Ok(None);
+        }
+        VillageAction::Quit => {
+            *game_running = false;
+            "Thank you for playing! Goodbye.".to_string()
+        }
+    };
+    Ok(Some(outcome))
+}
+
+// Lists the built-in themes and any palettes in the themes folder
+fn choose_theme(renderer: &mut Renderer) -> crossterm::Result<()> {
+    let current = settings::current();
+    let themes = theme::available();
+    let mut options: Vec<String> = themes
+        .iter()
+        .map(|name| if *name == current.theme { format!("{} (current)", name) } else { name.clone() })
+        .collect();
+    options.push("Back".to_string());
+    let option_refs: Vec<&str> = options.iter().map(|option| option.as_str()).collect();
+
+    let mut selected = themes.iter().position(|name| *name == current.theme).unwrap_or(0);
+    if let Some(name) = select_menu(renderer, Decision::Leave, &option_refs, &mut selected)?.and_then(|choice| themes.get(choice)) {
+        theme::apply(name, current.color_mode);
+        settings::update(|settings| settings.theme = name.clone())?;
+    }
+    Ok(())
+}
+
+// Shows what each action is bound to and where to change it, writing out the
+// default bindings file if there isn't one to edit yet
+fn view_bindings(renderer: &mut Renderer) -> crossterm::Result<()> {
+    clear_screen(renderer)?;
+    display_question(renderer, "Key bindings")?;
+    let mut lines: Vec<Line> = input::describe()
+        .into_iter()
+        .map(|(action, keys)| Line::plain(format!("{:<8} {}", action, keys)))
+        .collect();
+    lines.push(Line::default());
+    lines.push(match input::write_defaults() {
+        Ok(path) => Line::plain(format!("Edit {} and restart the game to change them.", path.display())),
+        Err(error) => Line::plain(format!("The bindings file couldn't be written: {}", error)),
+    });
+    layout::show(renderer, Region::Body, lines)?;
+
+    let back_options = ["Back"];
+    select_menu(renderer, Decision::Leave, &back_options, &mut 0)?;
+    Ok(())
+}
+
+// Picking an entry toggles or cycles it in place until the player backs out
+fn choose_effects(renderer: &mut Renderer) -> crossterm::Result<()> {
+    let mut selected = 0;
+    loop {
+        let current = settings::current();
+        let options = [
+            format!("Animations: {}", if current.animations { "on" } else { "off" }),
+            format!("Text speed: {}", current.text_speed.name()),
+            "Back".to_string(),
+        ];
+        let option_refs: Vec<&str> = options.iter().map(|option| option.as_str()).collect();
+        match select_menu(renderer, Decision::Leave, &option_refs, &mut selected)? {
+            Some(0) => settings::update(|settings| settings.animations = !settings.animations)?,
+            Some(1) => {
+                let speeds = TextSpeed::ALL;
+                let next = speeds[(speeds.iter().position(|speed| *speed == current.text_speed).unwrap_or(0) + 1) % speeds.len()];
+                settings::update(|settings| settings.text_speed = next)?;
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn train_qi(renderer: &mut Renderer, player: &mut Player, log: &mut ConsoleBuffer) -> crossterm::Result<String> {
+    clear_screen(renderer)?;
+    display_question(renderer, "Meditating. Keep your breath inside the flowing Qi.")?;
+
+    // Display the meditating figure
+    display_meditating_figure(renderer)?;
+
+    let result = meditation::meditate(renderer, player)?;
+    events::publish(player, log, GameEvent::QiTrained(result.qi_gained));
+
+    // Quiet meditation is when cultivation methods are understood
+    if !result.deviated {
+        techniques::comprehend(player, TechniqueKind::Cultivation, (result.qi_gained / 20) as u32);
+    }
+
+    if result.deviated {
+        Ok(format!(
+            "Your focus shattered and your Qi deviated! You lost {} Qi and injured your meridians.",
+            result.qi_lost
+        ))
+    } else {
+        Ok(format!(
+            "You meditated and gathered {} Qi, banishing {} heart demons. Your Qi level is now {}.",
+            result.qi_gained, result.demons_banished, player.qi
+        ))
+    }
+}
+
+fn attempt_breakthrough(renderer: &mut Renderer, player: &mut Player, log: &mut ConsoleBuffer) -> crossterm::Result<String> {
+    let (qi_needed, success_chance) = breakthrough_requirements(player.cultivation_level);
+    let target_level = player.cultivation_level + 1;
+    let is_major = tribulation::is_major(target_level);
+
+    // Show Qi requirement and success chance to the player
+    let prompt = if is_major {
+        format!(
+            "Attempting to break through to Level {}. This requires {} Qi and will call down a heavenly tribulation of {} lightning waves. Do you want to proceed?",
+            target_level,
+            qi_needed,
+            tribulation::wave_count(target_level)
+        )
+    } else {
+        format!(
+            "Attempting to break through to Level {}. This requires {} Qi and has a {:.0}% chance of success. Do you want to proceed?",
+            target_level,
+            qi_needed,
+            success_chance * 100.0
+        )
+    };
+
+    // Use the select_yes_no function
+    let proceed = select_yes_no(renderer, Decision::Breakthrough(player), &prompt)?;
+
+    if !proceed {
+        return Ok("You chose not to attempt the breakthrough at this time.".to_string());
+    }
+    if cultivation::max_qi(player) < qi_needed as i32 {
+        return Ok(format!(
+            "Your injured meridians can only hold {} Qi. Rest to heal them before attempting a breakthrough.",
+            cultivation::max_qi(player)
+        ));
+    }
+    if player.qi < qi_needed as i32 {
+        return Ok("Not enough Qi to attempt a breakthrough.".to_string());
+    }
+    events::publish(player, log, GameEvent::BreakthroughAttempted);
+
+    // Major breakthroughs are decided by surviving the tribulation instead of a single roll
+    let succeeded = if is_major {
+        match tribulation::endure(renderer, player)? {
+            TribulationOutcome::Survived => {
+                events::publish(player, log, GameEvent::TribulationSurvived);
+                true
+            }
+            TribulationOutcome::Abandoned { health_lost, qi_lost } => {
+                events::publish(player, log, GameEvent::BreakthroughFailed);
+                return Ok(format!(
+                    "You fled the tribulation, your body scorched and your meridians torn. You lost {} health and {} Qi.",
+                    health_lost, qi_lost
+                ));
+            }
+            TribulationOutcome::Died { wave } => {
+                die(
+                    renderer,
+                    player,
+                    &format!("Struck down by lightning wave {} of a heavenly tribulation", wave),
+                    &format!(
+                        "Lightning wave {} tears through your body. You are struck down by the wrath of heaven and earth...\nGame Over. You have died.",
+                        wave
+                    ),
+                );
+            }
+        }
+    } else {
+        random::rng().gen_bool(success_chance)
+    };
+
+    if succeeded {
+        ascend(player);
+        events::publish(player, log, GameEvent::BreakthroughSucceeded { level: player.cultivation_level });
+        if player.cultivation_level == 6 {
+            Ok("Incredible! You have transcended mortal bounds and become a Cultivation Immortal!".to_string())
+        } else {
+            Ok(format!("Congratulations! You have successfully ascended to Cultivation Level {}!", player.cultivation_level))
+        }
+    } else {
+        events::publish(player, log, GameEvent::BreakthroughFailed);
+        let qi_loss = fall_back(player);
+        Ok(format!("Breakthrough attempt failed. You lost {} Qi and injured your meridians. Rest and train before trying again.", qi_loss))
+    }
+}
+
+// A successful breakthrough pours all the Qi gathered into the new level
+fn ascend(player: &mut Player) {
+    player.cultivation_level += 1;
+    player.qi = 0;
+}
+
+// A failed minor breakthrough loses 30% of the Qi and strains the meridians.
+// Returns the Qi lost.
+fn fall_back(player: &mut Player) -> i32 {
+    let qi_loss = (player.qi as f32 * 0.3) as i32;
+    player.qi -= qi_loss;
+    cultivation::injure_meridians(player, 10);
+    qi_loss
+}
+
+// Resting restores health and mends the meridians a little. Returns whether
+// there were meridians to mend.
+fn rest(player: &mut Player) -> bool {
+    player.health = player.max_health;
+    let injured = player.meridian_damage > 0;
+    cultivation::heal_meridians(player, 10);
+    injured
+}
+
+fn breakthrough_requirements(cultivation_level: u32) -> (u32, f64) {
+    let (qi_needed, success_chance) = match cultivation_level {
+        1 => (100, 0.9),   // 90% chance of success for level 1
+        2 => (300, 0.7),   // 70% chance of success for level 2
+        3 => (900, 0.5),   // 50% chance of success for level 3
+        4 => (2000, 0.4),  // 40% chance of success for level 4
+        5 => (5000, 0.3),  // 30% chance of success for level 5
+        6 => (10000, 0.1), // 10% chance of success for level 6 (immortality)
+        _ => (u32::MAX, 0.0),
+    };
+    // The difficulty makes breakthroughs more or less likely, but never certain to fail or beyond certain
+    (qi_needed, (success_chance * difficulty::current().breakthrough_chance).min(1.0))
+}
+
+fn generate_enemy(player: &Player) -> Enemy {
+    let mut rng = random::rng();
+    let enemy_type = rng.gen_range(1..=3);
+    // Rogue cultivators can have any root and have hardened themselves against it
+    let rogue_element = element::ALL[rng.gen_range(0..element::ALL.len())];
+
+    // Scale enemy stats based on player's cultivation level
+    let scale_factor = player.cultivation_level as i32;
+    
+    let enemy = match enemy_type {
+        1 => Enemy {
+            name: EnemyKind::Bandit.name().to_string(),
+            kind: EnemyKind::Bandit,
+            health: 40 + (10 * scale_factor),
+            attack: 7 + (2 * scale_factor),
+            defense: 3 + (scale_factor),
+            is_boss: false,
+            element: Element::Metal,
+            resistances: Vec::new(),
+        },
+        2 => Enemy {
+            name: EnemyKind::RogueCultivator.name().to_string(),
+            kind: EnemyKind::RogueCultivator,
+            health: 60 + (15 * scale_factor),
+            attack: 12 + (3 * scale_factor),
+            defense: 5 + (2 * scale_factor),
+            is_boss: false,
+            element: rogue_element,
+            resistances: vec![rogue_element],
+        },
+        3 => Enemy {
+            name: EnemyKind::ShadowAssassin.name().to_string(),
+            kind: EnemyKind::ShadowAssassin,
+            health: 50 + (12 * scale_factor),
+            attack: 15 + (3 * scale_factor),
+            defense: 4 + (2 * scale_factor),
+            is_boss: false,
+            element: Element::Water,
+            resistances: vec![Element::Ice],
+        },
+        _ => unreachable!(),
+    };
+    scaled(enemy)
+}
+
+fn battle(
+    renderer: &mut Renderer,
+    player: &mut Player,
+    enemy: &mut Enemy,
+    battle_buffer: &mut ConsoleBuffer,
+    ) -> crossterm::Result<BattleOutcome> {
+    let enemy_max_health = enemy.health; // Enemies always start a fight unhurt
+    plain::forget(&enemy.name);
+
+    while player.health > 0 && enemy.health > 0 {
+        // Quitting mid battle saves the fight's wounds, not the state before it
+        if let Err(error) = save::refresh(player) {
+            battle_buffer.add_message(MessageKind::System, format!("Saving failed: {}", error));
+        }
+        clear_screen(renderer)?;
+
+        // Display player and enemy info, and then buffer messages
+        display_player_info(renderer, player)?;
+        display_enemy_info(renderer, enemy, enemy_max_health)?;
+        battle_buffer.display(renderer)?;
+
+        // Learned combat techniques sit between the basic actions and fleeing
+        let mut actions = vec![combat::Action::Attack, combat::Action::Qi, combat::Action::Defend];
+        actions.extend(techniques::combat_skills(player).into_iter().map(combat::Action::Technique));
+        actions.push(combat::Action::Flee);
+        let action_options: Vec<String> = actions
+            .iter()
+            .map(|action| match action {
+                combat::Action::Attack => "Attack".to_string(),
+                combat::Action::Qi => "Use Qi".to_string(),
+                combat::Action::Defend => "Defend".to_string(),
+                combat::Action::Technique(index) => {
+                    let technique = player.techniques[*index].technique;
+                    format!("{} ({} Qi)", technique.name, technique.qi_cost)
+                }
+                combat::Action::Flee => "Flee".to_string(),
+            })
+            .collect();
+        let option_refs: Vec<&str> = action_options.iter().map(|option| option.as_str()).collect();
+        let action = actions[select_option(renderer, Decision::Battle(player, enemy, &actions), &option_refs)?];
+        let enemy_health_before = enemy.health;
+        if combat::player_turn(player, enemy, action, battle_buffer) {
+            events::publish(player, battle_buffer, GameEvent::BattleFled);
+            return Ok(BattleOutcome::Fled);
+        }
+
+        // Qi attacks and techniques burst out before the damage lands
+        events::publish(player, battle_buffer, GameEvent::DamageDealt(enemy_health_before - enemy.health));
+        play_hit(renderer, Region::Enemy, enemy_health_before - enemy.health, enemy_max_health, action.uses_qi(), |renderer| {
+            display_enemy_info(renderer, enemy, enemy_max_health)
+        })?;
+
+        if enemy.health <= 0 {
+            combat::victory(player, enemy, battle_buffer);
+            if enemy.is_boss {
+                // The journey ends here, so the unlocks are announced on the way out
+                let mut message = "Congratulations! You have defeated the Ancient Demon Lord!".to_string();
+                for notice in achievements::take_notices() {
+                    message.push('\n');
+                    message.push_str(&notice);
+                }
+                end_journey(renderer, player, "Defeated the Ancient Demon Lord and brought peace to the land.", &message);
+            }
+            break;
+        }
+
+        // Enemy's turn
+        let player_health_before = player.health;
+        combat::enemy_turn(player, enemy, battle_buffer);
+
+        events::publish(player, battle_buffer, GameEvent::DamageTaken(player_health_before - player.health));
+        play_hit(renderer, Region::Stats, player_health_before - player.health, player.max_health, false, |renderer| {
+            display_player_info(renderer, player)
+        })?;
+
+        if player.health <= 0 {
+            die(
+                renderer,
+                player,
+                &format!("Slain by the {}", enemy.name),
+                &format!("You have been defeated by the {}...\nGame Over. You have died.", enemy.name),
+            );
+        }
+    }
+    Ok(BattleOutcome::Victory)
+}
+
+// Ends the journey in death. The fallen are laid to rest in the graveyard, and a
+// hardcore journey's save is buried with them. Autoplay deaths leave both alone.
+fn die(renderer: &mut Renderer, player: &Player, cause: &str, message: &str) -> ! {
+    if autoplay::active() {
+        end_journey(renderer, player, &format!("{}.", cause), message);
+    }
+    let rules = difficulty::current();
+    let grave = graveyard::Grave {
+        name: player.name.clone(),
+        class: player.class_type.name().to_string(),
+        level: player.cultivation_level,
+        cause: cause.to_string(),
+        enemies_defeated: player.enemies_defeated,
+        days: player.days,
+        difficulty: rules.name.clone(),
+        hardcore: rules.hardcore,
+    };
+
+    let mut message = message.to_string();
+    if let Err(error) = graveyard::bury(&grave) {
+        message.push_str(&format!("\nThe graveyard could not be updated: {}", error));
+    }
+    if rules.hardcore {
+        match save::delete(save::current_slot()) {
+            Ok(()) => message.push_str("\nYour journey was hardcore, and its save is gone with you."),
+            Err(error) => message.push_str(&format!("\nThe save could not be deleted: {}", error)),
+        }
+    }
+    end_journey(renderer, player, &format!("{}.", cause), &message)
+}
+
+// Shows the end-of-run report before leaving the game with the message
+fn end_journey(renderer: &mut Renderer, player: &Player, outcome: &str, message: &str) -> ! {
+    // Quitting from the report must not save a journey that is already over
+    save::forget_checkpoint();
+    if let Err(error) = stats::view(renderer, "Your journey has ended.", &Report::new(player, outcome), "Done") {
+        terminal::exit(&format!("{}\nThe report could not be shown: {}", message, error));
+    }
+    terminal::exit(message)
+}
+
+// Redraws the panel a blow landed on with its new health, then plays the hit over it
+fn play_hit(
+    renderer: &mut Renderer,
+    region: Region,
+    damage: i32,
+    max_health: i32,
+    qi_used: bool,
+    redraw: impl FnOnce(&mut Renderer) -> crossterm::Result<()>,
+    ) -> crossterm::Result<()> {
+    if damage <= 0 || !animation::enabled(renderer) {
+        return Ok(());
+    }
+    redraw(renderer)?;
+    let mut effects = animation::hit(region, damage, max_health);
+    if qi_used {
+        effects.push(Effect::QiBurst { region });
+    }
+    animation::play(renderer, &effects)
+}
+
+// The merchant's shelf stays open after each purchase until the player backs out.
+// Like buy_gear, returns None when nothing was bought and no time passes, with
+// anything worth telling the player already in the log.
+fn buy_techniques(renderer: &mut Renderer, player: &mut Player, menus: &mut MenuStack, log: &mut ConsoleBuffer) -> crossterm::Result<Option<String>> {
+    let manuals = techniques::unlearned(player);
+    if manuals.is_empty() {
+        menus.back();
+        log.add_message(MessageKind::System, "The merchant has no manuals you haven't already learned.".to_string());
+        return Ok(None);
+    }
+
+    // Mark the manuals matching the player's class so they stand out
+    let mut manual_options: Vec<String> = manuals
+        .iter()
+        .map(|technique| {
+            format!(
+                "{} - {} ({} gold){}",
+                technique.name,
+                techniques::kind_name(technique.kind),
+                technique.price,
+                if technique.affinity == player.class_type { " [affinity]" } else { "" }
+            )
+        })
+        .collect();
+    manual_options.push("Back".to_string());
+    let option_refs: Vec<&str> = manual_options.iter().map(|option| option.as_str()).collect();
+    let technique = match menus.select(renderer, Decision::TechniqueShop(player, &manuals), &option_refs)? {
+        Some(choice) if choice < manuals.len() => manuals[choice],
+        Some(_) => {
+            menus.back();
+            return Ok(None);
+        }
+        None => return Ok(None),
+    };
+
+    if player.gold < technique.price {
+        log.add_message(MessageKind::System, format!("You can't afford the {} manual.", technique.name));
+        return Ok(None);
+    }
+    player.gold -= technique.price;
+    techniques::learn(player, technique);
+    events::publish(player, log, GameEvent::GoldChanged(-technique.price));
+    events::publish(player, log, GameEvent::ItemAcquired { item: Item::Manual(technique), source: Source::Shop });
+    Ok(Some(format!("You bought the {} manual and begin to study it.", technique.name)))
+}
+
+fn buy_gear(renderer: &mut Renderer, player: &mut Player, menus: &mut MenuStack, log: &mut ConsoleBuffer) -> crossterm::Result<Option<String>> {
+    let mut gear_options: Vec<String> =
+        Gear::ALL.iter().map(|gear| format!("{} ({} gold)", gear.name(), gear.price())).collect();
+    gear_options.push("Back".to_string());
+    let option_refs: Vec<&str> = gear_options.iter().map(|option| option.as_str()).collect();
+    let gear = match menus.select(renderer, Decision::GearShop(player, &Gear::ALL), &option_refs)? {
+        Some(choice) if choice < Gear::ALL.len() => Gear::ALL[choice],
+        Some(_) => {
+            menus.back();
+            return Ok(None);
+        }
+        None => return Ok(None),
+    };
+
+    if player.gold < gear.price() {
+        log.add_message(MessageKind::System, "You don't have enough gold for that.".to_string());
+        return Ok(None);
+    }
+    player.gold -= gear.price();
+    let outcome = match gear {
+        Gear::IronFistGloves => {
+            player.attack += 5;
+            "You bought Iron Fist Gloves! Attack increased."
+        }
+        Gear::QiNecklace => {
+            cultivation::gain_qi(player, 10);
+            "You bought a Qi Enhancing Necklace! Qi increased."
+        }
+        Gear::ThunderWard => {
+            player.talismans += 1;
+            "You bought a Thunder Ward Talisman! It will shield you from one lightning strike."
+        }
+    };
+    events::publish(player, log, GameEvent::GoldChanged(-gear.price()));
+    events::publish(player, log, GameEvent::ItemAcquired { item: Item::Gear, source: Source::Shop });
+    Ok(Some(outcome.to_string()))
+}
+
+fn study_techniques(renderer: &mut Renderer, player: &mut Player) -> crossterm::Result<String> {
+    if player.techniques.is_empty() {
+        return Ok("You have no manuals to study. Buy or find some first.".to_string());
+    }
+
+    // Pore over every manual at once, slow but steady progress
+    for index in 0..player.techniques.len() {
+        techniques::comprehend_technique(player, index, 8);
+    }
+
+    clear_screen(renderer)?;
+    display_question(renderer, "You pore over your manuals by candlelight.")?;
+    let lines = player
+        .techniques
+        .iter()
+        .map(|learned| {
+            Line::plain(format!(
+                "{} ({}): {}% comprehended",
+                learned.technique.name,
+                techniques::kind_name(learned.technique.kind),
+                learned.comprehension
+            ))
+        })
+        .collect();
+    layout::show(renderer, Region::Body, lines)?;
+
+    // Esc works as well as picking Continue
+    let back_options = ["Continue"];
+    select_menu(renderer, Decision::Leave, &back_options, &mut 0)?;
+    Ok("You studied your techniques.".to_string())
+}
+
+// The first NPC whose quest is still open, or the first of them once every quest is done
+fn sought_npc(npcs: &[Npc]) -> usize {
+    npcs.iter().position(|npc| !npc.quest.completed).unwrap_or(0)
+}
+
+fn talk_to_npc(player: &mut Player, npcs: &mut [Npc], index: usize, log: &mut ConsoleBuffer) -> crossterm::Result<String> {
+    let npc = &npcs[index];
+    if !npc.quest.completed {
+        if npc.quest.goal.fulfilled(player) {
+            npcs[index].quest.completed = true;
+            let remaining = npcs.iter().filter(|npc| !npc.quest.completed).count();
+            let npc = &npcs[index];
+            player.gold += npc.quest.reward;
+            events::publish(player, log, GameEvent::GoldChanged(npc.quest.reward));
+            events::publish(player, log, GameEvent::QuestCompleted { remaining });
+            // The elder also passes on the cultivation method suited to the player's class
+            if let Some(technique) = techniques::affinity_cultivation_method(player) {
+                techniques::learn(player, technique);
+                events::publish(player, log, GameEvent::ItemAcquired { item: Item::Manual(technique), source: Source::Reward });
+                return Ok(format!(
+                    "Congratulations! Quest completed. You received {} gold and the {} manual!",
+                    npc.quest.reward, technique.name
+                ));
+            }
+            Ok(format!(
+                "Congratulations! Quest completed. You received {} gold!",
+                npc.quest.reward
+            ))
+        } else {
+            Ok(format!("{}: '{}'", npc.name, npc.quest.description))
+        }
+    } else {
+        Ok(format!(
+            "{}: You have already completed my quest.",
+            npc.name
+        ))
+    }
+}
+
+fn clear_screen(renderer: &mut Renderer) -> crossterm::Result<()> {
+    layout::clear(renderer)
+}
+
+fn display_question(renderer: &mut Renderer, question: &str) -> crossterm::Result<()> {
+    if plain::enabled() {
+        plain::say(question);
+        return Ok(());
+    }
+
+    let color = theme::color(Role::Question);
+    let line = Line::colored(question, color);
+    let header = match layout::rect(renderer, Region::Header)? {
+        Some(header) => header,
+        None => {
+            layout::retain(Region::Header, vec![line]);
+            return Ok(());
+        }
+    };
+
+    // Instant text speed writes the whole line at once, as does drawing anywhere
+    // but the terminal
+    let delay = settings::current().text_speed.delay();
+    if delay.is_zero() || !renderer.is_terminal() {
+        layout::show(renderer, Region::Header, vec![line])?;
+        return Ok(());
+    }
+
+    // Type the question out one more character each frame
+    let typed: Vec<char> = question.chars().take(header.width as usize).collect();
+    for length in 1..=typed.len() {
+        layout::draw(renderer, Region::Header, &[Line::colored(typed[..length].iter().collect::<String>(), color)])?;
+        renderer.present()?;
+        sleep(delay); // Delay for typewriter effect
+    }
+
+    // Remember the question so it comes back after a resize
+    layout::retain(Region::Header, vec![line]);
+    Ok(())
+}
+
+fn display_player_info(renderer: &mut Renderer, player: &Player) -> crossterm::Result<()> {
+    if plain::enabled() {
+        plain::report(&player.name, vec![
+            ("health", format!("{}/{}", player.health, player.max_health)),
+            ("Qi", format!("{}/{}", player.qi, cultivation::max_qi(player))),
+            ("attack", player.attack.to_string()),
+            ("defense", player.defense.to_string()),
+            ("meridian damage", format!("{}%", player.meridian_damage)),
+            ("cultivation level", player.cultivation_level.to_string()),
+            ("gold", player.gold.to_string()),
+        ]);
+        return Ok(());
+    }
+
+    let label = theme::color(Role::PlayerBorder);
+    let background = theme::color(Role::PlayerBackground);
+    let width = layout::rect(renderer, Region::Stats)?.map_or(36, |stats| stats.width as usize);
+    let inner = width.saturating_sub(2);
+
+    // Progress toward the Qi the next breakthrough needs, if there is one left
+    let (qi_needed, _) = breakthrough_requirements(player.cultivation_level);
+    let ascent = if qi_needed == u32::MAX {
+        widgets::field("Ascent", "peak reached", label, theme::color(Role::ProgressDone))
+    } else {
+        widgets::gauge("Ascent", player.qi, qi_needed as i32, inner, GaugeStyle::Progress, label)
+    };
+
+    let rows = vec![
+        widgets::gauge("Health", player.health, player.max_health, inner, GaugeStyle::Health, label),
+        widgets::gauge("Qi", player.qi, cultivation::max_qi(player), inner, GaugeStyle::Qi, label),
+        ascent,
+        widgets::field("Attack:", player.attack.to_string(), label, theme::color(Role::Attack)),
+        widgets::field("Defense:", player.defense.to_string(), label, theme::color(Role::Defense)),
+        widgets::field("Meridian damage:", format!("{}%", player.meridian_damage), label, theme::color(Role::Meridian)),
+        widgets::field("Cultivation level:", player.cultivation_level.to_string(), label, theme::color(Role::Level)),
+        widgets::field("Gold:", player.gold.to_string(), label, theme::color(Role::Gold)),
+        widgets::field("Spiritual root:", player.spiritual_root.name(), label, theme::color(Role::Root)),
+    ];
+
+    layout::show(renderer, Region::Stats, widgets::panel(&player.name, rows, width, label, background))
+}
+
+fn display_enemy_info(renderer: &mut Renderer, enemy: &Enemy, max_health: i32) -> crossterm::Result<()> {
+    if plain::enabled() {
+        plain::report(&enemy.name, vec![
+            ("health", format!("{}/{}", enemy.health, max_health)),
+            ("attack", enemy.attack.to_string()),
+            ("defense", enemy.defense.to_string()),
+            ("element", enemy.element.name().to_string()),
+        ]);
+        return Ok(());
+    }
+
+    let color = theme::color(Role::EnemyBorder);
+    let background = theme::color(Role::EnemyBackground);
+    let width = layout::rect(renderer, Region::Enemy)?.map_or(36, |enemy| enemy.width as usize);
+
+    let rows = vec![
+        widgets::gauge("Health", enemy.health, max_health, width.saturating_sub(2), GaugeStyle::Health, color),
+        widgets::field("Attack:", enemy.attack.to_string(), color, color),
+        widgets::field("Defense:", enemy.defense.to_string(), color, color),
+        widgets::field("Element:", enemy.element.name(), color, color),
+    ];
+
+    // The enemy's portrait goes in whatever room is left under the panel
+    let mut lines = widgets::panel(&enemy.name, rows, width, color, background);
+    let height = layout::rect(renderer, Region::Enemy)?.map_or(0, |enemy| enemy.height as usize);
+    lines.extend(art::render(art::Kind::Enemy, &enemy.name, width, height.saturating_sub(lines.len()), color));
+    layout::show(renderer, Region::Enemy, lines)
+}
+
+// A picture of where the player is, in the space the enemy panel takes in battle
+fn display_backdrop(renderer: &mut Renderer, screen: Screen) -> crossterm::Result<()> {
+    let location = match screen {
+        Screen::Locations => "crossroads",
+        Screen::Village | Screen::TechniqueShop | Screen::GearShop => "village",
+    };
+    let (width, height) = layout::rect(renderer, Region::Enemy)?
+        .map_or((36, 12), |enemy| (enemy.width as usize, enemy.height as usize));
+    let lines = art::render(art::Kind::Location, location, width, height, theme::color(Role::Title));
+    layout::show(renderer, Region::Enemy, lines)
+}
+
+// Line entry in raw mode, so Ctrl+C is caught and the terminal restored
+fn read_text(renderer: &mut Renderer, decision: Decision, prompt: &str) -> crossterm::Result<String> {
+    if let Some(text) = autoplay::text(decision, prompt) {
+        return Ok(text);
+    }
+    if plain::enabled() {
+        return Ok(plain::read_line(prompt));
+    }
+    let mut text = String::new();
+    let raw_mode = RawMode::enable(renderer)?;
+    loop {
+        layout::show(renderer, Region::Menu, vec![Line::plain(format!("{}{}", prompt, text))])?;
+        // Show the cursor right after the typed text
+        if let Some(menu) = layout::rect(renderer, Region::Menu)? {
+            let column = (prompt.chars().count() + text.chars().count()) as u16;
+            renderer.set_cursor(Some((menu.x + column.min(menu.width - 1), menu.y)));
+        }
+
+        match input::read(renderer)? {
+            Event::Key(key_event) if terminal::is_interrupt(&key_event) => terminal::exit(""),
+            Event::Key(key_event) => match key_event.code {
+                KeyCode::Enter => break,
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Char(c) => text.push(c),
+                _ => {}
+            },
+            Event::Resize(_, _) => layout::reflow(renderer)?,
+            _ => {}
+        }
+    }
+    drop(raw_mode);
+    renderer.set_cursor(None);
+    Ok(text.trim_end().to_string())
+}
+
+fn select_option(renderer: &mut Renderer, decision: Decision, options: &[&str]) -> crossterm::Result<usize> {
+    let mut selected = 0;
+    loop {
+        match choose(renderer, decision, options, &mut selected)? {
+            Pick::Chosen => return Ok(selected),
+            // There is no parent menu to go back to, so both ask about quitting
+            Pick::Back | Pick::Quit => confirm_quit(renderer)?, // Only returns if the player keeps playing
+        }
+    }
+}
+
+// A submenu that can be backed out of with Esc, returning None. The selection is
+// kept by the caller so reopening the menu starts where the player left off.
+fn select_menu(renderer: &mut Renderer, decision: Decision, options: &[&str], selected: &mut usize) -> crossterm::Result<Option<usize>> {
+    *selected = (*selected).min(options.len() - 1);
+    loop {
+        match choose(renderer, decision, options, selected)? {
+            Pick::Chosen => return Ok(Some(*selected)),
+            Pick::Back => return Ok(None),
+            Pick::Quit => confirm_quit(renderer)?,
+        }
+    }
+}
+
+// How a trip through a menu ended
+enum Pick {
+    Chosen,
+    Back,
+    Quit,
+}
+
+// Reads input until an option is picked, the player backs out or asks to quit.
+// The decision tells an autoplay policy what is being asked.
+fn choose(renderer: &mut Renderer, decision: Decision, options: &[&str], selected: &mut usize) -> crossterm::Result<Pick> {
+    if let Some(answer) = autoplay::choose(decision, options) {
+        return Ok(match answer {
+            autoplay::Answer::Choose(choice) => {
+                *selected = choice;
+                Pick::Chosen
+            }
+            autoplay::Answer::Back => Pick::Back,
+            autoplay::Answer::Quit => Pick::Quit,
+        });
+    }
+    if plain::enabled() {
+        return Ok(match plain::choose(options) {
+            plain::Reply::Number(choice) => {
+                *selected = choice;
+                Pick::Chosen
+            }
+            plain::Reply::Back => Pick::Back,
+            plain::Reply::Quit => Pick::Quit,
+        });
+    }
+
+    // Initial drawing of the options
+    print_options(renderer, options, *selected)?;
+
+    let raw_mode = RawMode::enable(renderer)?;
+    let picked = loop {
+        if !input::poll(renderer, Duration::from_millis(100))? {
+            continue;
+        }
+        let event = input::read(renderer)?;
+        if let Event::Resize(_, _) = event {
+            layout::reflow(renderer)?;
+            continue;
+        }
+        match input::action(&event) {
+            Some(Action::Up) => {
+                *selected = if *selected > 0 { *selected - 1 } else { options.len() - 1 };
+                print_options(renderer, options, *selected)?; // Update display
+            }
+            Some(Action::Down) => {
+                *selected = if *selected < options.len() - 1 { *selected + 1 } else { 0 };
+                print_options(renderer, options, *selected)?; // Update display
+            }
+            Some(Action::Confirm) => break Pick::Chosen,
+            Some(Action::Shortcut(index)) if index < options.len() => {
+                *selected = index;
+                break Pick::Chosen;
+            }
+            Some(Action::Click { column, row }) => {
+                if let Some(index) = option_at(renderer, options.len(), *selected, column, row)? {
+                    *selected = index;
+                    break Pick::Chosen;
+                }
+            }
+            Some(Action::Back) => break Pick::Back,
+            Some(Action::Quit) => break Pick::Quit,
+            _ => {}
+        }
+    };
+    drop(raw_mode);
+    Ok(picked)
+}
+
+// Asks before quitting and offers to save first. Returns only if the player keeps playing.
+fn confirm_quit(renderer: &mut Renderer) -> crossterm::Result<()> {
+    let header = layout::contents(Region::Header);
+    display_question(renderer, "Quit the game?")?;
+
+    // There is nothing to save before the player has been created
+    let can_save = save::has_checkpoint();
+    let choices = quit_options(can_save);
+    let options: Vec<&str> = choices.iter().map(|choice| choice.name(can_save)).collect();
+    let mut selected = 0;
+    if let Pick::Chosen = choose(renderer, Decision::Quit(choices), &options, &mut selected)? {
+        match choices[selected] {
+            QuitChoice::KeepPlaying => {}
+            QuitChoice::SaveAndQuit => match save::save_checkpoint(save::current_slot()) {
+                Ok(()) => terminal::exit("Your journey has been saved. Farewell!"),
+                Err(error) => {
+                    display_question(renderer, &format!("Saving failed: {}", error))?;
+                    renderer.present()?;
+                    sleep(Duration::from_secs(2));
+                }
+            },
+            QuitChoice::Quit => terminal::exit("Farewell!"),
+        }
+    }
+
+    layout::show(renderer, Region::Header, header)
+}
+
+// Ironman and hardcore journeys are saved as they go, so there is no leaving
+// without it. Quitting must never leave an older save to load, which could undo
+// a death that was on its way.
+fn quit_options(can_save: bool) -> &'static [QuitChoice] {
+    let rules = difficulty::current();
+    if can_save && (rules.ironman || rules.hardcore) {
+        &[QuitChoice::KeepPlaying, QuitChoice::SaveAndQuit]
+    } else if can_save {
+        &[QuitChoice::KeepPlaying, QuitChoice::SaveAndQuit, QuitChoice::Quit]
+    } else {
+        &[QuitChoice::KeepPlaying, QuitChoice::Quit]
+    }
+}
+
+// Scroll the list when there are more options than rows in the menu
+fn first_visible(selected: usize, height: usize) -> usize {
+    (selected + 1).saturating_sub(height)
+}
+
+fn print_options(renderer: &mut Renderer, options: &[&str], selected: usize) -> crossterm::Result<()> {
+    let height = layout::rect(renderer, Region::Menu)?.map_or(options.len(), |menu| menu.height as usize);
+
+    let lines = options
+        .iter()
+        .enumerate()
+        .skip(first_visible(selected, height))
+        .map(|(i, option)| {
+            // The first nine entries can be picked with their number key
+            let shortcut = if i < 9 { format!("{}.", i + 1) } else { "  ".to_string() };
+            Line::plain(format!("{} {} {}", if i == selected { "●" } else { "◯" }, shortcut, option))
+        })
+        .collect();
+
+    layout::show(renderer, Region::Menu, lines)
+}
+
+// The menu entry drawn at a clicked screen position, if any
+fn option_at(renderer: &Renderer, count: usize, selected: usize, column: u16, row: u16) -> crossterm::Result<Option<usize>> {
+    let menu = match layout::rect(renderer, Region::Menu)? {
+        Some(menu) => menu,
+        None => return Ok(None),
+    };
+    if column < menu.x || column >= menu.x + menu.width || row < menu.y || row >= menu.y + menu.height {
+        return Ok(None);
+    }
+    let index = first_visible(selected, menu.height as usize) + (row - menu.y) as usize;
+    Ok(Some(index).filter(|index| *index < count))
+}
+
+fn select_yes_no(renderer: &mut Renderer, decision: Decision, prompt: &str) -> crossterm::Result<bool> {
+    if let Some(answer) = autoplay::yes_no(decision, prompt) {
+        return Ok(answer);
+    }
+    if plain::enabled() {
+        return Ok(plain::yes_no(prompt));
+    }
+    let options = ["Yes", "No"];
+    let mut selected = 0;
+
+    let raw_mode = RawMode::enable(renderer)?;
+    let answer = loop {
+        // Display the prompt with the options on the line below it
+        let width = layout::rect(renderer, Region::Menu)?.map_or(prompt.len(), |menu| menu.width as usize);
+        let mut lines: Vec<Line> = layout::wrap(prompt, width).into_iter().map(Line::plain).collect();
+        let options_row = lines.len() as u16;
+        let mut option_spans = Vec::new();
+        for (i, option) in options.iter().enumerate() {
+            if i == selected {
+                option_spans.push(Span::colored(*option, theme::color(Role::Highlight)).underlined());
+            } else {
+                option_spans.push(Span::plain(*option));
+            }
+            option_spans.push(Span::plain(" "));
+        }
+        lines.push(Line::new(option_spans));
+        layout::show(renderer, Region::Menu, lines)?;
+
+        // Wait for input
+        if !input::poll(renderer, Duration::from_millis(100))? {
+            continue;
+        }
+        let event = input::read(renderer)?;
+        if let Event::Resize(_, _) = event {
+            layout::reflow(renderer)?;
+            continue;
+        }
+        match input::action(&event) {
+            Some(Action::Left) | Some(Action::Up) => selected = selected.saturating_sub(1),
+            Some(Action::Right) | Some(Action::Down) => selected = (selected + 1).min(options.len() - 1),
+            // True if 'Yes' selected, false if 'No' selected
+            Some(Action::Confirm) => break selected == 0,
+            Some(Action::Shortcut(index)) if index < options.len() => break index == 0,
+            Some(Action::Back) => break false,
+            Some(Action::Quit) => {
+                confirm_quit(renderer)?;
+            }
+            Some(Action::Click { column, row }) => {
+                // "Yes" takes the first four columns of the option line, "No" the rest
+                if let Some(menu) = layout::rect(renderer, Region::Menu)? {
+                    if row == menu.y + options_row && column >= menu.x {
+                        break column - menu.x < 4;
+                    }
+                }
+            }
+            _ => {}
+        }
+    };
+    drop(raw_mode);
+    Ok(answer)
+}
+
+fn display_meditating_figure(renderer: &mut Renderer) -> crossterm::Result<()> {
+    let body = layout::rect(renderer, Region::Body)?;
+    let (width, height) = body.map_or((80, 10), |body| (body.width as usize, body.height as usize));
+    let lines = art::render(art::Kind::Event, "meditation", width, height, theme::color(Role::Title));
+    layout::show(renderer, Region::Body, lines)
+}
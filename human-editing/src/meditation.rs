@@ -0,0 +1,252 @@
+use rand::Rng;
+use std::time::{Duration, Instant};
+use crossterm::{
+    event::{Event, KeyCode},
+};
+
+use crate::input::{self, Action};
+use crate::layout::{self, Line, Span, Region};
+use crate::renderer::Renderer;
+use crate::terminal::{self, RawMode};
+use crate::theme::{self, Role};
+use crate::autoplay::{self, Decision};
+use crate::{confirm_quit, cultivation, plain, random, techniques, Player};
+
+pub struct MeditationResult {
+    pub qi_gained: i32,
+    pub demons_banished: u32,
+    pub deviated: bool,
+    pub qi_lost: i32, // Only lost when the Qi deviates
+}
+
+// A heart demon waiting to be banished by pressing its key in time
+struct HeartDemon {
+    key: char,
+    appeared: Instant,
+}
+
+const BAR_WIDTH: f32 = 40.0;
+const ZONE_WIDTH: f32 = 8.0;
+const TICK: Duration = Duration::from_millis(100);
+const DEMON_PATIENCE: Duration = Duration::from_secs(2);
+const DEMON_KEYS: [char; 6] = ['a', 's', 'd', 'f', 'j', 'k'];
+
+pub fn meditate(renderer: &mut Renderer, player: &mut Player) -> crossterm::Result<MeditationResult> {
+    if plain::enabled() {
+        return Ok(meditate_plain(player));
+    }
+
+    let mut rng = random::rng();
+    let mut breath: f32 = BAR_WIDTH / 2.0;
+    let mut zone_center: f32 = BAR_WIDTH / 2.0;
+    let mut zone_velocity: f32 = 0.3;
+    let mut focus: i32 = 50;
+    let mut ticks: u32 = 0;
+    let mut demon: Option<HeartDemon> = None;
+    let mut message = format!(
+        "Tap {} to breathe in, the breath sinks between taps. Press {} to stop.",
+        input::keys(Action::Up).join(" or "),
+        input::keys(Action::Confirm).join(" or ")
+    );
+    let mut result = MeditationResult { qi_gained: 0, demons_banished: 0, deviated: false, qi_lost: 0 };
+
+    let raw_mode = RawMode::enable(renderer)?;
+    loop {
+        ticks += 1;
+        let mut inhaling = false;
+        let mut stop = false;
+
+        // Gather every key pressed during this tick so holding a key doesn't speed time up
+        let tick_start = Instant::now();
+        while let Some(remaining) = TICK.checked_sub(tick_start.elapsed()) {
+            if !input::poll(renderer, remaining)? {
+                break;
+            }
+            let event = input::read(renderer)?;
+            // A heart demon's key banishes it, whatever else the key is bound to
+            let banishes = match (&demon, &event) {
+                (Some(active), Event::Key(key_event)) if !terminal::is_interrupt(key_event) => {
+                    matches!(key_event.code, KeyCode::Char(c) if c.to_ascii_lowercase() == active.key)
+                }
+                _ => false,
+            };
+            if banishes {
+                demon = None;
+                focus = (focus + 20).min(100);
+                result.demons_banished += 1;
+                message = "You see through the illusion and the heart demon fades.".to_string();
+                continue;
+            }
+            match input::action(&event) {
+                Some(Action::Up) => inhaling = true,
+                Some(Action::Confirm) => {
+                    stop = true;
+                    break;
+                }
+                Some(Action::Quit) => confirm_quit(renderer)?,
+                _ if matches!(event, Event::Resize(_, _)) => layout::reflow(renderer)?,
+                _ => {}
+            }
+        }
+        if stop {
+            break;
+        }
+
+        // Each tick with a tap in it is a breath in, otherwise the breath sinks.
+        // Terminals report presses but not releases, so holding the key only keeps
+        // inhaling once key repeat kicks in.
+        breath = if inhaling { breath + 2.5 } else { breath - 0.6 }.clamp(0.0, BAR_WIDTH - 1.0);
+
+        // The target zone wanders, changing direction now and then
+        if rng.gen_bool(0.05) {
+            zone_velocity = -zone_velocity;
+        }
+        zone_center += zone_velocity;
+        if zone_center <= ZONE_WIDTH / 2.0 || zone_center >= BAR_WIDTH - ZONE_WIDTH / 2.0 {
+            zone_velocity = -zone_velocity;
+            zone_center = zone_center.clamp(ZONE_WIDTH / 2.0, BAR_WIDTH - ZONE_WIDTH / 2.0);
+        }
+
+        let in_zone = (breath - zone_center).abs() <= ZONE_WIDTH / 2.0;
+        if in_zone {
+            focus = (focus + 2).min(100);
+            // Long sessions give less and less, short focused sessions are best
+            let gain = tick_gain(player, ticks, focus);
+            result.qi_gained += cultivation::gain_qi(player, gain);
+        } else {
+            focus -= 3;
+        }
+
+        // Heart demons interrupt now and then and must be banished quickly
+        match &demon {
+            Some(active) if active.appeared.elapsed() >= DEMON_PATIENCE => {
+                demon = None;
+                focus -= 40;
+                message = "The heart demon's whispers shake your focus!".to_string();
+            }
+            None if rng.gen_bool(0.015) => {
+                let key = DEMON_KEYS[rng.gen_range(0..DEMON_KEYS.len())];
+                demon = Some(HeartDemon { key, appeared: Instant::now() });
+                message = format!("A heart demon whispers doubts! Press '{}' to banish it!", key);
+            }
+            _ => {}
+        }
+
+        // Losing all focus sends the Qi rushing the wrong way
+        if focus <= 0 {
+            deviate(player, &mut result);
+            break;
+        }
+
+        draw(renderer, player, breath, zone_center, focus, &message)?;
+    }
+    drop(raw_mode);
+
+    Ok(result)
+}
+
+// Qi flowing in one tick of steady breathing, smaller the longer the session runs
+fn tick_gain(player: &Player, ticks: u32, focus: i32) -> i32 {
+    let fatigue = 1.0 / (1.0 + ticks as f32 / 150.0);
+    let gain = techniques::effective_cultivation_speed(player)
+        * (1 + player.cultivation_level) as f32
+        * fatigue
+        * focus as f32
+        / 100.0;
+    gain.round().max(1.0) as i32
+}
+
+// Without the breathing bar the session plays out on its own, and a heart demon
+// that appears is banished by typing its letter
+fn meditate_plain(player: &mut Player) -> MeditationResult {
+    session(player, |key| {
+        let prompt = format!("A heart demon whispers doubts! Type '{}' to banish it: ", key);
+        let answer = autoplay::text(Decision::HeartDemon(key), &prompt).unwrap_or_else(|| plain::read_line(&prompt));
+        let banished = answer.to_lowercase() == key.to_string();
+        plain::say(if banished {
+            "You see through the illusion and the heart demon fades."
+        } else {
+            "The heart demon's whispers shake your focus!"
+        });
+        banished
+    })
+}
+
+// A session of meditation where each breath holds steady or slips by chance.
+// Whether a heart demon is banished is up to `banish`, given the demon's key;
+// failing shakes the focus, and the lower it falls the likelier the Qi is to deviate.
+pub fn session(player: &mut Player, mut banish: impl FnMut(char) -> bool) -> MeditationResult {
+    const SESSION_TICKS: u32 = 60;
+    const STEADY_BREATH: f64 = 0.8;
+    let mut rng = random::rng();
+    let mut result = MeditationResult { qi_gained: 0, demons_banished: 0, deviated: false, qi_lost: 0 };
+    let mut focus: i32 = 50;
+    let demon_tick = rng.gen_bool(0.5).then(|| rng.gen_range(10..SESSION_TICKS));
+
+    for ticks in 1..=SESSION_TICKS {
+        if demon_tick == Some(ticks) {
+            let key = DEMON_KEYS[rng.gen_range(0..DEMON_KEYS.len())];
+            if banish(key) {
+                focus = (focus + 20).min(100);
+                result.demons_banished += 1;
+            } else {
+                focus -= 40;
+                if rng.gen_bool(((50 - focus) as f64 / 50.0).clamp(0.0, 1.0)) {
+                    deviate(player, &mut result);
+                    return result;
+                }
+            }
+        }
+        if rng.gen_bool(STEADY_BREATH) {
+            focus = (focus + 2).min(100);
+            let gain = tick_gain(player, ticks, focus);
+            result.qi_gained += cultivation::gain_qi(player, gain);
+        } else {
+            focus -= 3;
+        }
+        if focus <= 0 {
+            deviate(player, &mut result);
+            return result;
+        }
+    }
+    result
+}
+
+// The Qi rushes the wrong way, scattering some of it and tearing the meridians
+fn deviate(player: &mut Player, result: &mut MeditationResult) {
+    result.deviated = true;
+    result.qi_lost = player.qi / 5;
+    player.qi -= result.qi_lost;
+    cultivation::injure_meridians(player, 15);
+}
+
+// The breathing bar and status go in the menu area below the meditating figure
+fn draw(renderer: &mut Renderer, player: &Player, breath: f32, zone_center: f32, focus: i32, message: &str) -> crossterm::Result<()> {
+    let breath_index = breath.round() as i32;
+    let bar: String = (0..BAR_WIDTH as i32)
+        .map(|i| {
+            if i == breath_index {
+                'O'
+            } else if (i as f32 - zone_center).abs() <= ZONE_WIDTH / 2.0 {
+                '='
+            } else {
+                '.'
+            }
+        })
+        .collect();
+
+    let lines = vec![
+        Line::new(vec![
+            Span::plain("Breath: "),
+            Span::colored(format!("[{}]", bar), theme::color(Role::QiHigh)),
+        ]),
+        Line::plain(format!(
+            "Qi level: {}/{}   Focus: {}%",
+            player.qi,
+            cultivation::max_qi(player),
+            focus.max(0)
+        )),
+        Line::plain(message),
+    ];
+    layout::show(renderer, Region::Menu, lines)
+}
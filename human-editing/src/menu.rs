@@ -0,0 +1,227 @@
+use crate::autoplay::Decision;
+use crate::renderer::Renderer;
+use crate::select_menu;
+
+// The menus the main loop can be showing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {
+    Locations,
+    Village,
+    TechniqueShop,
+    GearShop,
+}
+
+// The entries of the fixed menus, in the order they are shown
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TitleEntry {
+    Continue,
+    NewJourney,
+    Graveyard,
+}
+
+impl TitleEntry {
+    pub fn name(self) -> &'static str {
+        match self {
+            TitleEntry::Continue => "Continue your journey",
+            TitleEntry::NewJourney => "Begin a new journey",
+            TitleEntry::Graveyard => "Visit the graveyard",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Destination {
+    Wilds,
+    Village,
+    MessageLog,
+    Statistics,
+    Achievements,
+    Theme,
+    Effects,
+    KeyBindings,
+}
+
+impl Destination {
+    pub const ALL: [Destination; 8] = [
+        Destination::Wilds,
+        Destination::Village,
+        Destination::MessageLog,
+        Destination::Statistics,
+        Destination::Achievements,
+        Destination::Theme,
+        Destination::Effects,
+        Destination::KeyBindings,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Destination::Wilds => "In the wilds",
+            Destination::Village => "At a village",
+            Destination::MessageLog => "View message log",
+            Destination::Statistics => "Statistics",
+            Destination::Achievements => "Achievements",
+            Destination::Theme => "Change color theme",
+            Destination::Effects => "Animations and text speed",
+            Destination::KeyBindings => "Key bindings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VillageAction {
+    Rest,
+    BuyTechniques,
+    BuyGear,
+    Study,
+    TalkToNpc,
+    TrainQi,
+    AttemptBreakthrough,
+    Back,
+    Quit,
+}
+
+impl VillageAction {
+    pub const ALL: [VillageAction; 9] = [
+        VillageAction::Rest,
+        VillageAction::BuyTechniques,
+        VillageAction::BuyGear,
+        VillageAction::Study,
+        VillageAction::TalkToNpc,
+        VillageAction::TrainQi,
+        VillageAction::AttemptBreakthrough,
+        VillageAction::Back,
+        VillageAction::Quit,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VillageAction::Rest => "Rest at a village",
+            VillageAction::BuyTechniques => "Buy techniques",
+            VillageAction::BuyGear => "Buy gear",
+            VillageAction::Study => "Study techniques",
+            VillageAction::TalkToNpc => "Talk to NPC",
+            VillageAction::TrainQi => "Train Qi",
+            VillageAction::AttemptBreakthrough => "Attempt Breakthrough",
+            VillageAction::Back => "Back",
+            VillageAction::Quit => "Quit",
+        }
+    }
+}
+
+// The gear shop's shelf, followed by Back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gear {
+    IronFistGloves,
+    QiNecklace,
+    ThunderWard,
+}
+
+impl Gear {
+    pub const ALL: [Gear; 3] = [Gear::IronFistGloves, Gear::QiNecklace, Gear::ThunderWard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Gear::IronFistGloves => "Iron Fist Gloves",
+            Gear::QiNecklace => "Qi Enhancing Necklace",
+            Gear::ThunderWard => "Thunder Ward Talisman",
+        }
+    }
+
+    pub fn price(self) -> i32 {
+        match self {
+            Gear::IronFistGloves => 30,
+            Gear::QiNecklace => 20,
+            Gear::ThunderWard => 15,
+        }
+    }
+}
+
+// What to do about an enemy met in the wilds. Backing out is the same as running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encounter {
+    Fight,
+    Run,
+}
+
+impl Encounter {
+    pub const ALL: [Encounter; 2] = [Encounter::Fight, Encounter::Run];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Encounter::Fight => "Fight",
+            Encounter::Run => "Run",
+        }
+    }
+}
+
+// The ways out of the quit prompt. Quit leaves without saving when there is a
+// journey to save.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuitChoice {
+    KeepPlaying,
+    SaveAndQuit,
+    Quit,
+}
+
+impl QuitChoice {
+    pub fn name(self, can_save: bool) -> &'static str {
+        match self {
+            QuitChoice::KeepPlaying => "Keep playing",
+            QuitChoice::SaveAndQuit => "Save and quit",
+            QuitChoice::Quit if can_save => "Quit without saving",
+            QuitChoice::Quit => "Quit",
+        }
+    }
+}
+
+// Submenus are pushed on top of the menu they were opened from, so backing out
+// of one returns to its parent. Every screen keeps the entry that was selected
+// when it was last left, even after being closed and opened again.
+pub struct MenuStack {
+    screens: Vec<(Screen, usize)>,
+    remembered: Vec<(Screen, usize)>,
+}
+
+impl MenuStack {
+    pub fn new(root: Screen) -> Self {
+        MenuStack { screens: vec![(root, 0)], remembered: Vec::new() }
+    }
+
+    pub fn current(&self) -> Screen {
+        self.screens.last().map_or(Screen::Locations, |(screen, _)| *screen)
+    }
+
+    pub fn push(&mut self, screen: Screen) {
+        let selected = self
+            .remembered
+            .iter()
+            .find(|(remembered, _)| *remembered == screen)
+            .map_or(0, |(_, selected)| *selected);
+        self.screens.push((screen, selected));
+    }
+
+    // Returns to the parent menu. The root menu has nowhere to go back to and stays put.
+    pub fn back(&mut self) {
+        if self.screens.len() > 1 {
+            if let Some((screen, selected)) = self.screens.pop() {
+                self.remembered.retain(|(remembered, _)| *remembered != screen);
+                self.remembered.push((screen, selected));
+            }
+        }
+    }
+
+    // Shows the current screen's options starting from its remembered selection.
+    // Backing out pops to the parent menu and returns None.
+    pub fn select(&mut self, renderer: &mut Renderer, decision: Decision, options: &[&str]) -> crossterm::Result<Option<usize>> {
+        let mut selected = self.screens.last().map_or(0, |(_, selected)| *selected);
+        let choice = select_menu(renderer, decision, options, &mut selected)?;
+        if let Some(top) = self.screens.last_mut() {
+            top.1 = selected;
+        }
+        if choice.is_none() {
+            self.back();
+        }
+        Ok(choice)
+    }
+}
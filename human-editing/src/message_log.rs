@@ -0,0 +1,264 @@
+use std::time::Duration;
+use crossterm::{
+    style::Color,
+    event::{Event, KeyCode},
+};
+
+use crate::events::{GameEvent, Item, Source};
+use crate::input::{self, Action};
+use crate::layout::{self, Line, Region, Span};
+use crate::renderer::Renderer;
+use crate::terminal::{self, RawMode};
+use crate::theme::{self, Role};
+use crate::{clear_screen, confirm_quit, display_question, plain, settings, Player};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageKind {
+    Combat,
+    Loot,
+    Quest,
+    System,
+    Achievement,
+}
+
+const KINDS: [MessageKind; 5] = [
+    MessageKind::Combat,
+    MessageKind::Loot,
+    MessageKind::Quest,
+    MessageKind::System,
+    MessageKind::Achievement,
+];
+
+impl MessageKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageKind::Combat => "combat",
+            MessageKind::Loot => "loot",
+            MessageKind::Quest => "quest",
+            MessageKind::System => "system",
+            MessageKind::Achievement => "achievement",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        theme::color(match self {
+            MessageKind::Combat => Role::Combat,
+            MessageKind::Loot => Role::Loot,
+            MessageKind::Quest => Role::Quest,
+            MessageKind::System => Role::System,
+            MessageKind::Achievement => Role::Gold,
+        })
+    }
+}
+
+// Tells the player what happened. Items bought or given as a reward are already
+// announced by the shop or whoever gave them.
+pub fn on_event(_player: &mut Player, log: &mut ConsoleBuffer, event: &GameEvent) {
+    match *event {
+        GameEvent::EnemyDefeated(kind) => {
+            log.add_message(MessageKind::Combat, format!("You have defeated the {}!", kind.name()));
+        }
+        GameEvent::ItemAcquired { item: Item::QiPill, source: Source::Loot } => log.add_message(
+            MessageKind::Loot,
+            "You found a Qi pill! Your cultivation speed increases by 1%.".to_string(),
+        ),
+        GameEvent::ItemAcquired { item: Item::Manual(technique), source: Source::Loot } => {
+            log.add_message(MessageKind::Loot, format!("You found a tattered manual on the body: {}!", technique.name));
+        }
+        _ => {}
+    }
+}
+
+pub struct Message {
+    pub kind: MessageKind,
+    pub text: String,
+}
+
+// Every message of the run, the main view only shows the newest few
+pub struct ConsoleBuffer {
+    messages: Vec<Message>,
+}
+
+impl ConsoleBuffer {
+    pub fn new() -> Self {
+        ConsoleBuffer { messages: Vec::new() }
+    }
+
+    pub fn add_message(&mut self, kind: MessageKind, message: String) {
+        // Plain mode has no log panel, messages are read out as they come
+        if plain::enabled() {
+            plain::say(&message);
+        }
+        self.messages.push(Message { kind, text: message });
+    }
+
+    pub fn display(&self, renderer: &mut Renderer) -> crossterm::Result<()> {
+        // Wrap long messages and keep the newest lines that fit in the log
+        let log = match layout::rect(renderer, Region::Log)? {
+            Some(log) => log,
+            None => return layout::show(renderer, Region::Log, Vec::new()),
+        };
+        let visible = settings::current().log_lines.min(log.height as usize);
+        let recent = &self.messages[self.messages.len().saturating_sub(visible)..];
+        let mut lines = wrapped_lines(recent.iter(), log.width as usize);
+        let overflow = lines.len().saturating_sub(visible);
+        lines.drain(..overflow);
+
+        layout::show(renderer, Region::Log, lines)
+    }
+}
+
+fn wrapped_lines<'a>(messages: impl Iterator<Item = &'a Message>, width: usize) -> Vec<Line> {
+    messages
+        .flat_map(|message| {
+            layout::wrap(&message.text, width)
+                .into_iter()
+                .map(move |line| Line::colored(line, message.kind.color()))
+        })
+        .collect()
+}
+
+// Full screen history viewer with scrolling, a category filter and text search
+pub fn view_history(renderer: &mut Renderer, buffer: &ConsoleBuffer) -> crossterm::Result<()> {
+    let mut scroll: usize = 0; // Lines scrolled up from the newest message
+    let mut filter: Option<MessageKind> = None;
+    let mut query = String::new();
+    let mut typing = false;
+
+    // Plain mode reads the whole history out at once
+    if plain::enabled() {
+        plain::say(&format!("Message log, {} messages:", buffer.messages.len()));
+        for message in &buffer.messages {
+            plain::say(&format!("{}: {}", message.kind.name(), message.text));
+        }
+        return Ok(());
+    }
+
+    clear_screen(renderer)?;
+    display_question(renderer, "Message log")?;
+
+    let raw_mode = RawMode::enable(renderer)?;
+    loop {
+        let body = layout::rect(renderer, Region::Body)?;
+        let page = body.map_or(10, |body| body.height as usize);
+        let width = body.map_or(80, |body| body.width as usize);
+
+        let needle = query.to_lowercase();
+        let shown = buffer.messages.iter().filter(|message| {
+            filter.is_none_or(|kind| message.kind == kind)
+                && (needle.is_empty() || message.text.to_lowercase().contains(&needle))
+        });
+        let lines = wrapped_lines(shown, width);
+        let max_scroll = lines.len().saturating_sub(page);
+        scroll = scroll.min(max_scroll);
+        let end = lines.len() - scroll;
+        let visible = lines[end.saturating_sub(page)..end].to_vec();
+        layout::show(renderer, Region::Body, visible)?;
+
+        let search_line = if typing {
+            Line::new(vec![Span::plain("Search: "), Span::colored(format!("{}_", query), theme::color(Role::Input))])
+        } else if !query.is_empty() {
+            Line::plain(format!("Showing messages containing \"{}\"", query))
+        } else {
+            Line::default()
+        };
+        let help = vec![
+            Line::plain(format!(
+                "Lines {}-{} of {}   Filter: {}   Main view shows {} lines",
+                end.saturating_sub(page) + usize::from(end > 0),
+                end,
+                lines.len(),
+                filter.map_or("all", |kind| kind.name()),
+                settings::current().log_lines
+            )),
+            search_line,
+            Line::plain("PgUp/PgDn scroll  / search  Tab filter  +/- main view lines  Esc close"),
+        ];
+        layout::show(renderer, Region::Menu, help)?;
+
+        if !input::poll(renderer, Duration::from_millis(100))? {
+            continue;
+        }
+        let event = input::read(renderer)?;
+        if let Event::Resize(_, _) = event {
+            layout::reflow(renderer)?;
+            continue;
+        }
+
+        // While typing a search, keys go into the query
+        if typing {
+            let key_event = match event {
+                Event::Key(key_event) => key_event,
+                _ => continue,
+            };
+            if terminal::is_interrupt(&key_event) {
+                confirm_quit(renderer)?;
+                continue;
+            }
+            match key_event.code {
+                KeyCode::Char(c) => query.push(c),
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Enter => typing = false,
+                KeyCode::Esc => {
+                    query.clear();
+                    typing = false;
+                }
+                _ => {}
+            }
+            scroll = 0;
+            continue;
+        }
+
+        match input::action(&event) {
+            Some(Action::Up) => {
+                scroll += 1;
+                continue;
+            }
+            Some(Action::Down) => {
+                scroll = scroll.saturating_sub(1);
+                continue;
+            }
+            Some(Action::Back) => break,
+            Some(Action::Quit) => {
+                confirm_quit(renderer)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        let key_event = match event {
+            Event::Key(key_event) => key_event,
+            _ => continue,
+        };
+        match key_event.code {
+            KeyCode::PageUp => scroll += page,
+            KeyCode::PageDown => scroll = scroll.saturating_sub(page),
+            KeyCode::Home => scroll = max_scroll,
+            KeyCode::End => scroll = 0,
+            KeyCode::Char('/') => {
+                query.clear();
+                typing = true;
+            }
+            KeyCode::Tab => {
+                // Cycle all -> combat -> loot -> quest -> system -> achievement -> all
+                filter = match filter {
+                    None => Some(KINDS[0]),
+                    Some(kind) => KINDS.iter().position(|k| *k == kind).and_then(|i| KINDS.get(i + 1)).copied(),
+                };
+                scroll = 0;
+            }
+            KeyCode::Char('+') => {
+                settings::update(|settings| settings.log_lines = (settings.log_lines + 1).min(50))?;
+            }
+            KeyCode::Char('-') => {
+                settings::update(|settings| settings.log_lines = settings.log_lines.saturating_sub(1).max(1))?;
+            }
+            KeyCode::Char('q') => break,
+            _ => {}
+        }
+    }
+    drop(raw_mode);
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use std::cell::{Cell, RefCell};
+use std::io::{self, BufRead, Write};
+
+use crate::layout::{Line, Region};
+use crate::terminal;
+
+// Named values describing a subject, like `("health", "84/120")`
+type Fields = Vec<(&'static str, String)>;
+
+// Plain mode talks in ordinary lines of text: no cursor movement, colors or
+// animation, and choices are typed in by number. It works with screen readers
+// and when the game's input or output is piped.
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    // The last reported state of each subject, so only changes are described
+    static REPORTED: RefCell<Vec<(String, Fields)>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn enable() {
+    ENABLED.with(|enabled| enabled.set(true));
+}
+
+pub fn enabled() -> bool {
+    ENABLED.with(|enabled| enabled.get())
+}
+
+// What the player typed at a menu
+pub enum Reply {
+    Number(usize),
+    Back,
+    Quit,
+}
+
+pub fn say(text: &str) {
+    println!("{}", text);
+}
+
+// Prints what a region would have shown. Questions, panels and the log are left
+// out since they are said as they happen, and menus are printed when asked.
+pub fn show(region: Region, lines: &[Line]) {
+    if matches!(region, Region::Body | Region::Screen) {
+        for line in lines {
+            let text: String = line.spans.iter().map(|span| span.text.as_str()).collect();
+            let text = text.trim_end();
+            if !text.is_empty() {
+                say(text);
+            }
+        }
+    }
+}
+
+// Reads one line, ending the game quietly when input runs out
+pub fn read_line(prompt: &str) -> String {
+    print!("{}", prompt);
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => terminal::exit(""),
+        Ok(_) => line.trim().to_string(),
+    }
+}
+
+// Lists the options by number and asks until it gets a number, `b` for back or `q` to quit
+pub fn choose(options: &[&str]) -> Reply {
+    for (i, option) in options.iter().enumerate() {
+        say(&format!("{}. {}", i + 1, option));
+    }
+    loop {
+        let answer = read_line("> ").to_lowercase();
+        match answer.as_str() {
+            "b" | "back" => return Reply::Back,
+            "q" | "quit" => return Reply::Quit,
+            _ => {}
+        }
+        match answer.parse::<usize>() {
+            Ok(number) if (1..=options.len()).contains(&number) => return Reply::Number(number - 1),
+            _ => say(&format!("Type a number from 1 to {}, b to go back or q to quit.", options.len())),
+        }
+    }
+}
+
+pub fn yes_no(prompt: &str) -> bool {
+    loop {
+        match read_line(&format!("{} (y/n) ", prompt)).to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" | "b" | "back" => return false,
+            _ => say("Type y or n."),
+        }
+    }
+}
+
+// Describes a subject's state: everything the first time, afterwards only what changed
+pub fn report(subject: &str, fields: Fields) {
+    let previous = REPORTED.with(|reported| {
+        reported
+            .borrow()
+            .iter()
+            .find(|(known, _)| known == subject)
+            .map(|(_, fields)| fields.clone())
+    });
+
+    let description = match &previous {
+        None => fields
+            .iter()
+            .map(|(name, value)| format!("{} {}", name, value))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(previous) => fields
+            .iter()
+            .filter_map(|(name, value)| {
+                let before = previous.iter().find(|(known, _)| known == name).map(|(_, before)| before);
+                match before {
+                    Some(before) if before == value => None,
+                    Some(before) => Some(format!("{} {} -> {}", name, before, value)),
+                    None => Some(format!("{} {}", name, value)),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    if !description.is_empty() {
+        say(&format!("{}: {}.", subject, description));
+    }
+
+    REPORTED.with(|reported| {
+        let mut reported = reported.borrow_mut();
+        reported.retain(|(known, _)| known != subject);
+        reported.push((subject.to_string(), fields));
+    });
+}
+
+// Forgets a subject so the next report describes it in full, for each new enemy
+pub fn forget(subject: &str) {
+    REPORTED.with(|reported| reported.borrow_mut().retain(|(known, _)| known != subject));
+}
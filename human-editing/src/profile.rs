@@ -0,0 +1,37 @@
+use std::fs;
+use std::io;
+
+// Progress kept across every journey, apart from the save slots so starting
+// over or dying on hardcore never loses it
+const PROFILE_FILE: &str = "profile.txt";
+
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub achievements: Vec<String>, // Ids of the unlocked achievements, oldest first
+}
+
+// A missing or unreadable profile is a fresh one. Unknown lines are skipped so
+// older games can read profiles written by newer ones.
+pub fn load() -> Profile {
+    let contents = fs::read_to_string(PROFILE_FILE).unwrap_or_default();
+    let mut profile = Profile::default();
+    for line in contents.lines() {
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        if key == "achievement" && !profile.achievements.iter().any(|known| known == value) {
+            profile.achievements.push(value.to_string());
+        }
+    }
+    profile
+}
+
+// The profile is `key = value` lines, one `achievement` line per unlock
+pub fn save(profile: &Profile) -> io::Result<()> {
+    let mut contents = String::new();
+    for id in &profile.achievements {
+        contents.push_str(&format!("achievement = {}\n", id));
+    }
+    fs::write(PROFILE_FILE, contents)
+}
@@ -0,0 +1,25 @@
+use crate::events::GameEvent;
+use crate::message_log::{ConsoleBuffer, MessageKind};
+use crate::{EnemyKind, Player};
+
+// What a quest asks of the player
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Goal {
+    DefeatBandits(u32),
+}
+
+impl Goal {
+    pub fn fulfilled(self, player: &Player) -> bool {
+        match self {
+            Goal::DefeatBandits(count) => player.bandits_defeated >= count,
+        }
+    }
+}
+
+// Keeps the progress quests are measured by
+pub fn on_event(player: &mut Player, log: &mut ConsoleBuffer, event: &GameEvent) {
+    if let GameEvent::EnemyDefeated(EnemyKind::Bandit) = event {
+        player.bandits_defeated += 1;
+        log.add_message(MessageKind::Quest, format!("You have defeated {} bandits so far.", player.bandits_defeated));
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::{self, Write};
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType},
+};
+
+use crate::plain;
+
+// Screens are drawn into a frame of cells first. Presenting the frame writes out
+// only the cells that differ from what the target already shows, so redrawing a
+// whole screen never blanks it in between. The game draws through one renderer,
+// handed down to everything that draws or waits for a key.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Color,
+    pub bg: Color,
+    pub underlined: bool,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell { ch: ' ', fg: Color::Reset, bg: Color::Reset, underlined: false };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    fn blank(width: u16, height: u16) -> Frame {
+        Frame { width, height, cells: vec![Cell::BLANK; width as usize * height as usize] }
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y as usize * self.width as usize + x as usize)
+    }
+}
+
+pub struct Renderer {
+    target: Box<dyn Write>,
+    size: Option<(u16, u16)>, // Set for targets that aren't the terminal
+    front: Option<Frame>,     // What the target shows, None when that's unknown
+    back: Frame,              // What it shows after the next present
+    cursor: Option<(u16, u16)>,
+    cursor_shown: bool,
+}
+
+impl Renderer {
+    // Draws on the terminal, at whatever size it is
+    pub fn terminal() -> Renderer {
+        Renderer::new(Box::new(io::stdout()), None)
+    }
+
+    // Draws into any writer as if it were a terminal of the given size
+    pub fn new(target: Box<dyn Write>, size: Option<(u16, u16)>) -> Renderer {
+        Renderer { target, size, front: None, back: Frame::blank(0, 0), cursor: None, cursor_shown: false }
+    }
+
+    // Whether the game is drawn on a real terminal, which has a keyboard, raw mode
+    // and someone watching the animations
+    pub fn is_terminal(&self) -> bool {
+        self.size.is_none()
+    }
+
+    // The size of what is being drawn on
+    pub fn size(&self) -> crossterm::Result<(u16, u16)> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => terminal::size(),
+        }
+    }
+
+    // Blanks the frame being drawn, at the current size
+    pub fn clear(&mut self) -> crossterm::Result<()> {
+        self.back()?.cells.fill(Cell::BLANK);
+        Ok(())
+    }
+
+    // Writes cells into the frame from a position onward, dropping any that fall off it
+    pub fn put(&mut self, x: u16, y: u16, cells: impl IntoIterator<Item = Cell>) -> crossterm::Result<()> {
+        let back = self.back()?;
+        for (offset, cell) in cells.into_iter().enumerate() {
+            let Some(index) = u16::try_from(offset).ok().and_then(|offset| back.index(x.checked_add(offset)?, y)) else {
+                break;
+            };
+            back.cells[index] = cell;
+        }
+        Ok(())
+    }
+
+    // Where the blinking cursor goes, or None to hide it
+    pub fn set_cursor(&mut self, position: Option<(u16, u16)>) {
+        self.cursor = position;
+    }
+
+    // Forgets what the target shows, so the next present redraws all of it. Needed
+    // when something else may have drawn over it, like a resize.
+    pub fn invalidate(&mut self) {
+        self.front = None;
+    }
+
+    // The frame being drawn, blanked first if the size has changed since
+    fn back(&mut self) -> crossterm::Result<&mut Frame> {
+        let (width, height) = self.size()?;
+        if (self.back.width, self.back.height) != (width, height) {
+            self.back = Frame::blank(width, height);
+        }
+        Ok(&mut self.back)
+    }
+
+    // Brings the target up to date with the frame. Called whenever the game is about
+    // to wait, for a key or between animation frames. Plain mode has no frame to show.
+    pub fn present(&mut self) -> crossterm::Result<()> {
+        if plain::enabled() {
+            return Ok(());
+        }
+        self.back()?;
+        let back = &self.back;
+        let target = &mut self.target;
+        // A new size or an unknown screen means starting over from a blank one
+        let front = match self.front.take() {
+            Some(front) if front.width == back.width && front.height == back.height => front,
+            _ => {
+                queue!(target, SetForegroundColor(Color::Reset), SetBackgroundColor(Color::Reset), Clear(ClearType::All))?;
+                Frame::blank(back.width, back.height)
+            }
+        };
+
+        let mut pen = Cell::BLANK;
+        for y in 0..back.height {
+            let mut in_place = false; // Whether the cursor is already where the next cell goes
+            for x in 0..back.width {
+                let index = y as usize * back.width as usize + x as usize;
+                let cell = back.cells[index];
+                if cell == front.cells[index] {
+                    in_place = false;
+                    continue;
+                }
+                if !in_place {
+                    queue!(target, cursor::MoveTo(x, y))?;
+                    in_place = true;
+                }
+                if cell.fg != pen.fg {
+                    queue!(target, SetForegroundColor(cell.fg))?;
+                }
+                if cell.bg != pen.bg {
+                    queue!(target, SetBackgroundColor(cell.bg))?;
+                }
+                if cell.underlined != pen.underlined {
+                    let attribute = if cell.underlined { Attribute::Underlined } else { Attribute::NoUnderline };
+                    queue!(target, SetAttribute(attribute))?;
+                }
+                queue!(target, Print(cell.ch))?;
+                pen = cell;
+            }
+        }
+        if pen != Cell::BLANK {
+            queue!(target, SetForegroundColor(Color::Reset), SetBackgroundColor(Color::Reset), SetAttribute(Attribute::NoUnderline))?;
+        }
+
+        match self.cursor {
+            Some((x, y)) => {
+                queue!(target, cursor::MoveTo(x, y))?;
+                if !self.cursor_shown {
+                    queue!(target, cursor::Show)?;
+                }
+            }
+            None if self.cursor_shown => queue!(target, cursor::Hide)?,
+            None => {}
+        }
+        self.cursor_shown = self.cursor.is_some();
+        self.front = Some(self.back.clone());
+        target.flush()?;
+        Ok(())
+    }
+}
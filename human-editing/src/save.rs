@@ -0,0 +1,260 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::difficulty::{self, Ruleset};
+use crate::element::{self, Element};
+use crate::stats::Stats;
+use crate::techniques::{self, LearnedTechnique};
+use crate::{autoplay, ClassType, Npc, Player};
+
+const SAVE_DIR: &str = "saves";
+pub const DEFAULT_SLOT: u32 = 1;
+
+thread_local! {
+    // The game as it stands, written out when quitting. Taken at the start of
+    // every turn and brought up to date through battles and tribulations.
+    static CHECKPOINT: RefCell<Option<String>> = const { RefCell::new(None) };
+    // Whether each NPC's quest was done at the last checkpoint, which nothing mid-turn changes
+    static QUESTS: RefCell<Vec<bool>> = const { RefCell::new(Vec::new()) };
+    static DIR: RefCell<PathBuf> = RefCell::new(PathBuf::from(SAVE_DIR));
+    // The slot the journey was loaded from, and is saved back to
+    static SLOT: Cell<u32> = const { Cell::new(DEFAULT_SLOT) };
+}
+
+pub fn use_slot(slot: u32) {
+    SLOT.with(|current| current.set(slot));
+}
+
+pub fn current_slot() -> u32 {
+    SLOT.with(|current| current.get())
+}
+
+// Keeps saves somewhere else from here on
+#[cfg(test)]
+pub fn use_dir(dir: PathBuf) {
+    DIR.with(|current| *current.borrow_mut() = dir);
+}
+
+pub fn path(slot: u32) -> PathBuf {
+    DIR.with(|dir| dir.borrow().join(format!("slot{}.sav", slot)))
+}
+
+pub fn exists(slot: u32) -> bool {
+    path(slot).is_file()
+}
+
+// Removes a slot's save, which is fine if there never was one
+pub fn delete(slot: u32) -> io::Result<()> {
+    match fs::remove_file(path(slot)) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+// Remembers the current state so it can be saved from any menu. Ironman and
+// hardcore journeys are written out right away, so neither quitting nor killing
+// the game can go back to an older state.
+pub fn checkpoint(player: &Player, npcs: &[Npc]) -> io::Result<()> {
+    QUESTS.with(|quests| *quests.borrow_mut() = npcs.iter().map(|npc| npc.quest.completed).collect());
+    store(player)
+}
+
+// Brings the checkpoint up to date partway through a turn, like each round of a
+// battle. Does nothing before the first checkpoint or after the journey is over.
+pub fn refresh(player: &Player) -> io::Result<()> {
+    if !has_checkpoint() {
+        return Ok(());
+    }
+    store(player)
+}
+
+fn store(player: &Player) -> io::Result<()> {
+    let contents = QUESTS.with(|quests| serialize(player, &quests.borrow()));
+    CHECKPOINT.with(|checkpoint| *checkpoint.borrow_mut() = Some(contents));
+    let rules = difficulty::current();
+    // The bot's journeys are never saved
+    if (rules.ironman || rules.hardcore) && !autoplay::active() {
+        save_checkpoint(current_slot())?;
+    }
+    Ok(())
+}
+
+// Once a journey is over there is nothing left to save
+pub fn forget_checkpoint() {
+    CHECKPOINT.with(|checkpoint| *checkpoint.borrow_mut() = None);
+}
+
+pub fn has_checkpoint() -> bool {
+    CHECKPOINT.with(|checkpoint| checkpoint.borrow().is_some())
+}
+
+// Writes the last checkpoint to the slot
+pub fn save_checkpoint(slot: u32) -> io::Result<()> {
+    let contents = CHECKPOINT
+        .with(|checkpoint| checkpoint.borrow().clone())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "nothing to save yet"))?;
+    let dir = DIR.with(|dir| dir.borrow().clone());
+    fs::create_dir_all(dir)?;
+    fs::write(path(slot), contents)
+}
+
+// Saves are `key = value` lines, with one `technique` line per learned manual,
+// a `rules.` line for each rule of the difficulty and a `stats.` line for each statistic.
+// Whether each NPC's quest is done goes on one line, in the order the NPCs are met.
+fn serialize(player: &Player, quests: &[bool]) -> String {
+    let mut contents = String::new();
+    let mut field = |key: &str, value: String| contents.push_str(&format!("{} = {}\n", key, value));
+
+    field("name", player.name.clone());
+    field("class", class_name(player.class_type).to_string());
+    field("health", player.health.to_string());
+    field("max_health", player.max_health.to_string());
+    field("attack", player.attack.to_string());
+    field("defense", player.defense.to_string());
+    field("qi", player.qi.to_string());
+    field("cultivation_level", player.cultivation_level.to_string());
+    field("gold", player.gold.to_string());
+    field("bandits_defeated", player.bandits_defeated.to_string());
+    field("cultivation_speed", player.cultivation_speed.to_string());
+    field("qi_pills", player.qi_pills.to_string());
+    field("talismans", player.talismans.to_string());
+    field("meridian_damage", player.meridian_damage.to_string());
+    field("spiritual_root", player.spiritual_root.name().to_string());
+    let completed: Vec<String> = quests.iter().map(|completed| completed.to_string()).collect();
+    field("quest_completed", completed.join(", "));
+    field("enemies_defeated", player.enemies_defeated.to_string());
+    field("days", player.days.to_string());
+    field("gear_bought", player.gear_bought.to_string());
+    // The whole ruleset is kept so editing a ruleset file doesn't change journeys already under way
+    let ruleset = difficulty::current();
+    field("difficulty", ruleset.name.clone());
+    for (key, value) in difficulty::serialize(&ruleset) {
+        field(&format!("rules.{}", key), value);
+    }
+    for (key, _, value) in player.stats.fields() {
+        field(&format!("stats.{}", key), value.to_string());
+    }
+    for learned in &player.techniques {
+        field("technique", format!("{}:{}", learned.technique.name, learned.comprehension));
+    }
+    contents
+}
+
+pub fn load(slot: u32, npcs: &mut [Npc]) -> io::Result<Player> {
+    let contents = fs::read_to_string(path(slot))?;
+    let mut values = HashMap::new();
+    let mut techniques = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| invalid(format!("malformed line \"{}\"", line)))?;
+        let (key, value) = (key.trim(), value.trim());
+        if key == "technique" {
+            techniques.push(parse_technique(value)?);
+        } else {
+            values.insert(key, value);
+        }
+    }
+
+    let class = get::<String>(&values, "class")?;
+    let health: i32 = get(&values, "health")?;
+    // Saves from before maximum health was tracked only have the current value
+    let max_health = get(&values, "max_health").unwrap_or_else(|_| health.max(100));
+    let root = get::<String>(&values, "spiritual_root")?;
+    let mut player = Player {
+        name: get(&values, "name")?,
+        class_type: class_from_name(&class).ok_or_else(|| invalid(format!("unknown class \"{}\"", class)))?,
+        health,
+        max_health,
+        attack: get(&values, "attack")?,
+        defense: get(&values, "defense")?,
+        qi: get(&values, "qi")?,
+        cultivation_level: get(&values, "cultivation_level")?,
+        gold: get(&values, "gold")?,
+        bandits_defeated: get(&values, "bandits_defeated")?,
+        cultivation_speed: get(&values, "cultivation_speed")?,
+        qi_pills: get(&values, "qi_pills")?,
+        talismans: get(&values, "talismans")?,
+        meridian_damage: get(&values, "meridian_damage")?,
+        techniques,
+        spiritual_root: element_from_name(&root).ok_or_else(|| invalid(format!("unknown element \"{}\"", root)))?,
+        // Older saves didn't count these yet
+        enemies_defeated: get(&values, "enemies_defeated").unwrap_or(0),
+        days: get(&values, "days").unwrap_or(0),
+        // There is no telling whether an older journey bought gear, so assume it did
+        gear_bought: get(&values, "gear_bought").unwrap_or(true),
+        stats: Stats::default(),
+    };
+    // Statistics missing from older saves start from zero
+    for (key, value) in &values {
+        if let Some(stat) = key.strip_prefix("stats.") {
+            if !player.stats.apply(stat, value) {
+                return Err(invalid(format!("bad statistic \"{}\"", stat)));
+            }
+        }
+    }
+    // NPCs missing from the line, like any added since the save, still have their quests open
+    let completed = get::<String>(&values, "quest_completed")?;
+    for (npc, completed) in npcs.iter_mut().zip(completed.split(',')) {
+        npc.quest.completed = completed.trim().parse().map_err(|_| invalid("bad value for \"quest_completed\"".to_string()))?;
+    }
+
+    // Saves from before difficulties were added were played on normal. Loading
+    // makes the journey's ruleset the active one.
+    let name = values.get("difficulty").copied().unwrap_or("normal");
+    let mut ruleset = Ruleset { name: name.to_string(), ..difficulty::normal() };
+    for (key, value) in &values {
+        if let Some(rule) = key.strip_prefix("rules.") {
+            if !difficulty::apply(&mut ruleset, rule, value) {
+                return Err(invalid(format!("bad rule \"{}\"", rule)));
+            }
+        }
+    }
+    difficulty::set(ruleset);
+
+    Ok(player)
+}
+
+fn get<T: FromStr>(values: &HashMap<&str, &str>, key: &str) -> io::Result<T> {
+    let value = values.get(key).ok_or_else(|| invalid(format!("missing \"{}\"", key)))?;
+    value.parse().map_err(|_| invalid(format!("bad value for \"{}\"", key)))
+}
+
+fn parse_technique(value: &str) -> io::Result<LearnedTechnique> {
+    let (name, comprehension) = value.rsplit_once(':').ok_or_else(|| invalid(format!("malformed technique \"{}\"", value)))?;
+    let technique = techniques::CATALOG
+        .iter()
+        .find(|technique| technique.name == name)
+        .ok_or_else(|| invalid(format!("unknown technique \"{}\"", name)))?;
+    let comprehension = comprehension.parse::<u32>().map_err(|_| invalid(format!("bad comprehension for \"{}\"", name)))?;
+    Ok(LearnedTechnique { technique, comprehension: comprehension.min(100) })
+}
+
+fn class_name(class_type: ClassType) -> &'static str {
+    match class_type {
+        ClassType::MartialArtist => "MartialArtist",
+        ClassType::QiCultivator => "QiCultivator",
+        ClassType::Assassin => "Assassin",
+    }
+}
+
+fn class_from_name(name: &str) -> Option<ClassType> {
+    [ClassType::MartialArtist, ClassType::QiCultivator, ClassType::Assassin]
+        .into_iter()
+        .find(|class_type| class_name(*class_type) == name)
+}
+
+fn element_from_name(name: &str) -> Option<Element> {
+    element::ALL.into_iter().find(|element| element.name() == name)
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::animation::TextSpeed;
+use crate::theme::ColorMode;
+
+pub const SETTINGS_FILE: &str = "settings.cfg";
+
+// Player preferences, stored as `key = value` lines in settings.cfg
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub log_lines: usize, // How many recent messages the main view shows
+    pub theme: String,    // A built-in theme or a palette in the themes folder
+    pub color_mode: Option<ColorMode>, // None detects what the terminal supports
+    pub animations: bool,  // Combat effects like floating damage numbers
+    pub text_speed: TextSpeed, // How fast questions are typed out
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            log_lines: 5,
+            theme: "classic".to_string(),
+            color_mode: None,
+            animations: true,
+            text_speed: TextSpeed::Normal,
+        }
+    }
+}
+
+thread_local! {
+    static SETTINGS: RefCell<Settings> = RefCell::new(Settings::default());
+    // Where the settings were loaded from and get saved back to
+    static FILE: RefCell<PathBuf> = RefCell::new(PathBuf::from(SETTINGS_FILE));
+}
+
+pub fn current() -> Settings {
+    SETTINGS.with(|settings| settings.borrow().clone())
+}
+
+// Changes the settings for the rest of the session and writes them to disk
+pub fn update(change: impl FnOnce(&mut Settings)) -> io::Result<()> {
+    SETTINGS.with(|settings| change(&mut settings.borrow_mut()));
+    save()
+}
+
+// Loads the settings file if it exists. Unknown keys and bad values are skipped so
+// an old or hand-edited file never stops the game from starting.
+pub fn load(path: &Path) {
+    FILE.with(|file| *file.borrow_mut() = path.to_path_buf());
+    let mut settings = Settings::default();
+    if let Ok(contents) = fs::read_to_string(path) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                apply(&mut settings, key.trim(), value.trim());
+            }
+        }
+    }
+    SETTINGS.with(|current| *current.borrow_mut() = settings);
+}
+
+fn apply(settings: &mut Settings, key: &str, value: &str) {
+    match key {
+        "log_lines" => {
+            if let Ok(lines) = value.parse::<usize>() {
+                settings.log_lines = lines.clamp(1, 50);
+            }
+        }
+        "theme" if !value.is_empty() => settings.theme = value.to_string(),
+        "color_mode" => {
+            if let Some(mode) = ColorMode::parse(value) {
+                settings.color_mode = mode;
+            }
+        }
+        "animations" => match value {
+            "on" => settings.animations = true,
+            "off" => settings.animations = false,
+            _ => {}
+        },
+        "text_speed" => {
+            if let Some(speed) = TextSpeed::parse(value) {
+                settings.text_speed = speed;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn save() -> io::Result<()> {
+    let settings = current();
+    let contents = format!(
+        "log_lines = {}\ntheme = {}\ncolor_mode = {}\nanimations = {}\ntext_speed = {}\n",
+        settings.log_lines,
+        settings.theme,
+        ColorMode::name(settings.color_mode),
+        if settings.animations { "on" } else { "off" },
+        settings.text_speed.name()
+    );
+    FILE.with(|file| fs::write(&*file.borrow(), contents))
+}
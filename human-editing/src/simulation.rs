@@ -0,0 +1,377 @@
+use rand::Rng;
+
+use crate::combat::{self, Action};
+use crate::message_log::ConsoleBuffer;
+use crate::tribulation::{self, Defense, TribulationOutcome};
+use crate::{
+    ascend, breakthrough_requirements, cultivation, difficulty, fall_back, generate_boss, generate_enemy, meditation,
+    new_player, random, rest, ClassType, Enemy, Player,
+};
+
+// Battles that drag on this long are called off and count as lost
+const MAX_TURNS: u32 = 200;
+// Days a run may spend training for a breakthrough, and the attempts it may
+// make, before it counts as stuck
+const MAX_TRAINING_DAYS: u32 = 300;
+const MAX_ATTEMPTS: u32 = 5;
+// How often a simulated player types a heart demon's letter in time
+const DEMON_BANISH_CHANCE: f64 = 0.8;
+// The Ancient Demon Lord waits at the last level
+const BOSS_LEVEL: u32 = 5;
+const CLASSES: [ClassType; 3] = [ClassType::MartialArtist, ClassType::QiCultivator, ClassType::Assassin];
+
+// A scripted way of fighting and cultivating, standing in for the player
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Policy {
+    AlwaysAttack, // Attacks, and braces against every lightning strike
+    QiFirst,      // Qi attacks whenever there is the Qi for them, and channels Qi against lightning
+    Defensive,    // Braces when hurt, runs when badly hurt and only breaks through when whole
+}
+
+impl Policy {
+    pub const ALL: [Policy; 3] = [Policy::AlwaysAttack, Policy::QiFirst, Policy::Defensive];
+
+    pub fn parse(name: &str) -> Option<Policy> {
+        Policy::ALL.into_iter().find(|policy| policy.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Policy::AlwaysAttack => "attack",
+            Policy::QiFirst => "qi-first",
+            Policy::Defensive => "defensive",
+        }
+    }
+
+    fn choose(self, player: &Player, enemy: &Enemy) -> Action {
+        match self {
+            Policy::AlwaysAttack => Action::Attack,
+            Policy::QiFirst if player.qi >= cultivation::qi_skill_cost(player) => Action::Qi,
+            Policy::QiFirst => Action::Attack,
+            Policy::Defensive if player.health * 4 < player.max_health && !enemy.is_boss => Action::Flee,
+            Policy::Defensive if player.health * 2 < player.max_health => Action::Defend,
+            Policy::Defensive => Action::Attack,
+        }
+    }
+
+    // Whether to attempt the breakthrough now rather than meditate some more
+    fn ready_to_break_through(self, player: &Player, qi_needed: i32) -> bool {
+        if player.qi < qi_needed {
+            return false;
+        }
+        let major = tribulation::is_major(player.cultivation_level + 1);
+        match self {
+            Policy::AlwaysAttack => true,
+            // Every bit of Qi above the requirement can absorb lightning
+            Policy::QiFirst => !major || player.qi >= cultivation::max_qi(player),
+            Policy::Defensive => player.meridian_damage == 0,
+        }
+    }
+
+    // How to meet a lightning strike, or None to abandon the tribulation
+    fn defend(self, player: &Player, strike: i32) -> Option<Defense> {
+        match self {
+            Policy::QiFirst if player.qi >= strike => Some(Defense::Channel),
+            Policy::Defensive if player.talismans > 0 => Some(Defense::Talisman),
+            Policy::Defensive if player.health <= strike => None,
+            _ => Some(Defense::Brace(tribulation::roll_timing(player))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub battles: u32,           // Separate battles per class, policy and level
+    pub runs: u32,              // Runs from level 1 to the boss per class and policy
+    pub battles_per_level: u32, // Battles a run fights before moving up a level
+    pub policies: Vec<Policy>,
+    pub difficulty: Option<String>,
+    pub seed: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            battles: 1000,
+            runs: 200,
+            battles_per_level: 10,
+            policies: Policy::ALL.to_vec(),
+            difficulty: None,
+            seed: None,
+        }
+    }
+}
+
+// The results for one class and policy at one cultivation level
+#[derive(Debug, Clone, Default)]
+pub struct Row {
+    pub class: &'static str,
+    pub policy: &'static str,
+    pub level: u32,
+    pub battles: u32,
+    pub wins: u32,
+    pub fled: u32,
+    pub turns: u32,
+    pub health_left: f64, // Summed over won battles, as a fraction of maximum health
+    pub runs: u32,
+    pub runs_survived: u32, // Runs that won the level's battles and broke through, or beat the boss
+    pub gold: i64,          // Summed over the surviving runs
+}
+
+impl Row {
+    pub fn win_rate(&self) -> f64 {
+        ratio(self.wins as f64, self.battles)
+    }
+
+    pub fn flee_rate(&self) -> f64 {
+        ratio(self.fled as f64, self.battles)
+    }
+
+    pub fn average_turns(&self) -> f64 {
+        ratio(self.turns as f64, self.battles)
+    }
+
+    pub fn average_health_left(&self) -> f64 {
+        ratio(self.health_left, self.wins)
+    }
+
+    pub fn survival_rate(&self) -> f64 {
+        ratio(self.runs_survived as f64, self.runs)
+    }
+
+    pub fn average_gold(&self) -> f64 {
+        ratio(self.gold as f64, self.runs_survived)
+    }
+}
+
+fn ratio(total: f64, count: u32) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+enum Outcome {
+    Won,
+    Lost,
+    Fled,
+}
+
+// Plays out the simulation, failing only when the difficulty doesn't exist
+pub fn run(config: &Config) -> Result<Vec<Row>, String> {
+    if let Some(seed) = config.seed {
+        random::seed(seed);
+    }
+    let ruleset = match &config.difficulty {
+        Some(name) => difficulty::find(name).ok_or_else(|| format!("there is no difficulty called `{}`", name))?,
+        None => difficulty::normal(),
+    };
+    difficulty::set(ruleset);
+
+    let mut rows = Vec::new();
+    for class in CLASSES {
+        for &policy in &config.policies {
+            let mut class_rows: Vec<Row> = (1..=BOSS_LEVEL)
+                .map(|level| Row { class: class.name(), policy: policy.name(), level, ..Row::default() })
+                .collect();
+            for row in class_rows.iter_mut() {
+                simulate_battles(row, class, policy, config.battles);
+            }
+            for _ in 0..config.runs {
+                simulate_run(&mut class_rows, class, policy, config.battles_per_level);
+            }
+            rows.extend(class_rows);
+        }
+    }
+    Ok(rows)
+}
+
+// Fresh characters at the row's level against the enemies found there
+fn simulate_battles(row: &mut Row, class: ClassType, policy: Policy, battles: u32) {
+    for _ in 0..battles {
+        let mut player = new_player(String::from("Simulated"), class);
+        player.cultivation_level = row.level;
+        let mut enemy = enemy_for(&player);
+        let (outcome, turns) = fight(&mut player, &mut enemy, policy);
+        row.battles += 1;
+        row.turns += turns;
+        match outcome {
+            Outcome::Won => {
+                row.wins += 1;
+                row.health_left += player.health as f64 / player.max_health as f64;
+            }
+            Outcome::Fled => row.fled += 1,
+            Outcome::Lost => {}
+        }
+    }
+}
+
+// One character from level 1 to the boss. Between battles it rests, keeping the
+// gold and loot of each win, then meditates and breaks through to the next level
+// as the policy sees fit. The run is over at the first defeat.
+fn simulate_run(rows: &mut [Row], class: ClassType, policy: Policy, battles_per_level: u32) {
+    let mut player = new_player(String::from("Simulated"), class);
+    player.cultivation_level = 1;
+    for row in rows.iter_mut() {
+        row.runs += 1;
+        let battles = if row.level >= BOSS_LEVEL { 1 } else { battles_per_level };
+        for _ in 0..battles {
+            rest(&mut player);
+            let mut enemy = enemy_for(&player);
+            match fight(&mut player, &mut enemy, policy).0 {
+                Outcome::Won => {
+                    let mut log = ConsoleBuffer::new();
+                    combat::victory(&mut player, &enemy, &mut log);
+                    combat::loot(&mut player, &enemy, &mut log);
+                }
+                Outcome::Fled => {}
+                Outcome::Lost => return,
+            }
+            cultivation::regenerate_qi(&mut player);
+        }
+        if row.level < BOSS_LEVEL && !break_through(&mut player, policy) {
+            return;
+        }
+        row.runs_survived += 1;
+        row.gold += player.gold as i64;
+    }
+}
+
+// Meditates and attempts breakthroughs until one succeeds. False if the character
+// died in a tribulation or never got there.
+fn break_through(player: &mut Player, policy: Policy) -> bool {
+    let (qi_needed, success_chance) = breakthrough_requirements(player.cultivation_level);
+    let qi_needed = qi_needed as i32;
+    let mut attempts = 0;
+    for _ in 0..MAX_TRAINING_DAYS {
+        rest(player);
+        if attempts == MAX_ATTEMPTS {
+            return false;
+        }
+        if policy.ready_to_break_through(player, qi_needed) {
+            attempts += 1;
+            let succeeded = if tribulation::is_major(player.cultivation_level + 1) {
+                match endure(player, policy) {
+                    TribulationOutcome::Survived => true,
+                    TribulationOutcome::Abandoned { .. } => false,
+                    TribulationOutcome::Died { .. } => return false,
+                }
+            } else {
+                let succeeded = random::rng().gen_bool(success_chance);
+                if !succeeded {
+                    fall_back(player);
+                }
+                succeeded
+            };
+            if succeeded {
+                ascend(player);
+                return true;
+            }
+        } else if cultivation::max_qi(player) >= qi_needed {
+            // Injured meridians that can't hold the Qi are left to heal over the days of rest
+            meditation::session(player, |_| random::rng().gen_bool(DEMON_BANISH_CHANCE));
+        }
+        cultivation::regenerate_qi(player);
+    }
+    false
+}
+
+// A heavenly tribulation met the way the policy meets lightning
+fn endure(player: &mut Player, policy: Policy) -> TribulationOutcome {
+    let target_level = player.cultivation_level + 1;
+    for wave in 1..=tribulation::wave_count(target_level) {
+        let strike = tribulation::strike_power(target_level, wave);
+        let defense = match policy.defend(player, strike) {
+            Some(defense) => defense,
+            None => return tribulation::abandon(player),
+        };
+        tribulation::withstand(player, strike, defense);
+        if player.health <= 0 {
+            return TribulationOutcome::Died { wave };
+        }
+    }
+    TribulationOutcome::Survived
+}
+
+fn enemy_for(player: &Player) -> Enemy {
+    if player.cultivation_level >= BOSS_LEVEL {
+        generate_boss()
+    } else {
+        generate_enemy(player)
+    }
+}
+
+fn fight(player: &mut Player, enemy: &mut Enemy, policy: Policy) -> (Outcome, u32) {
+    // Nobody reads the log, it just keeps the messages the turns write
+    let mut log = ConsoleBuffer::new();
+    for turn in 1..=MAX_TURNS {
+        if combat::player_turn(player, enemy, policy.choose(player, enemy), &mut log) {
+            return (Outcome::Fled, turn);
+        }
+        if enemy.health <= 0 {
+            return (Outcome::Won, turn);
+        }
+        combat::enemy_turn(player, enemy, &mut log);
+        if player.health <= 0 {
+            return (Outcome::Lost, turn);
+        }
+    }
+    (Outcome::Lost, MAX_TURNS)
+}
+
+const COLUMNS: [&str; 10] = [
+    "class", "policy", "level", "battles", "win_rate", "flee_rate", "average_turns", "health_left", "runs_survived", "average_gold",
+];
+
+fn values(row: &Row) -> [String; 10] {
+    [
+        row.class.to_string(),
+        row.policy.to_string(),
+        row.level.to_string(),
+        row.battles.to_string(),
+        format!("{:.3}", row.win_rate()),
+        format!("{:.3}", row.flee_rate()),
+        format!("{:.2}", row.average_turns()),
+        format!("{:.3}", row.average_health_left()),
+        format!("{:.3}", row.survival_rate()),
+        format!("{:.1}", row.average_gold()),
+    ]
+}
+
+// The rows lined up in columns for reading in a terminal
+pub fn table(rows: &[Row]) -> String {
+    let cells: Vec<[String; 10]> = rows.iter().map(values).collect();
+    let widths: Vec<usize> = (0..COLUMNS.len())
+        .map(|column| cells.iter().map(|row| row[column].len()).chain([COLUMNS[column].len()]).max().unwrap_or(0))
+        .collect();
+    let line = |fields: Vec<&str>| {
+        fields
+            .iter()
+            .zip(&widths)
+            .map(|(field, width)| format!("{:<width$}", field, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut text = line(COLUMNS.to_vec());
+    text.push('\n');
+    for row in &cells {
+        text.push_str(&line(row.iter().map(|field| field.as_str()).collect()));
+        text.push('\n');
+    }
+    text
+}
+
+// The rows as CSV with a header line. No value ever holds a comma or quote.
+pub fn csv(rows: &[Row]) -> String {
+    let mut text = COLUMNS.join(",");
+    text.push('\n');
+    for row in rows {
+        text.push_str(&values(row).join(","));
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,234 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::difficulty;
+use crate::events::{GameEvent, Item, Location};
+use crate::layout::{self, Line, Region};
+use crate::renderer::Renderer;
+use crate::message_log::ConsoleBuffer;
+use crate::theme::{self, Role};
+use crate::autoplay::Decision;
+use crate::{clear_screen, display_question, select_menu, Player};
+
+const REPORTS_DIR: &str = "reports";
+
+// What happened over a journey, counted as it is played and kept in the save
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub battles_won: u32,
+    pub battles_fled: u32,
+    pub qi_trained: u32, // Qi gathered by meditating, not what flows back on its own
+    pub breakthroughs_attempted: u32,
+    pub breakthroughs_failed: u32, // Failed rolls and fled tribulations
+    pub gold_earned: u32,
+    pub gold_spent: u32,
+    pub pills_used: u32,
+    pub wilds_explored: u32,
+    pub village_visits: u32,
+}
+
+impl Stats {
+    // Every statistic as its key, label and value, in the order they are shown
+    pub fn fields(&self) -> [(&'static str, &'static str, u32); 12] {
+        [
+            ("damage_dealt", "Damage dealt", self.damage_dealt),
+            ("damage_taken", "Damage taken", self.damage_taken),
+            ("battles_won", "Battles won", self.battles_won),
+            ("battles_fled", "Battles fled", self.battles_fled),
+            ("qi_trained", "Qi trained", self.qi_trained),
+            ("breakthroughs_attempted", "Breakthroughs attempted", self.breakthroughs_attempted),
+            ("breakthroughs_failed", "Breakthroughs failed", self.breakthroughs_failed),
+            ("gold_earned", "Gold earned", self.gold_earned),
+            ("gold_spent", "Gold spent", self.gold_spent),
+            ("pills_used", "Qi pills used", self.pills_used),
+            ("wilds_explored", "Trips into the wilds", self.wilds_explored),
+            ("village_visits", "Village visits", self.village_visits),
+        ]
+    }
+
+    // Sets one statistic by key, returning false for unknown keys and bad values
+    pub fn apply(&mut self, key: &str, value: &str) -> bool {
+        let field = match key {
+            "damage_dealt" => &mut self.damage_dealt,
+            "damage_taken" => &mut self.damage_taken,
+            "battles_won" => &mut self.battles_won,
+            "battles_fled" => &mut self.battles_fled,
+            "qi_trained" => &mut self.qi_trained,
+            "breakthroughs_attempted" => &mut self.breakthroughs_attempted,
+            "breakthroughs_failed" => &mut self.breakthroughs_failed,
+            "gold_earned" => &mut self.gold_earned,
+            "gold_spent" => &mut self.gold_spent,
+            "pills_used" => &mut self.pills_used,
+            "wilds_explored" => &mut self.wilds_explored,
+            "village_visits" => &mut self.village_visits,
+            _ => return false,
+        };
+        match value.parse() {
+            Ok(value) => {
+                *field = value;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+// Counts the statistics as the journey publishes what happens in it
+pub fn on_event(player: &mut Player, _log: &mut ConsoleBuffer, event: &GameEvent) {
+    let stats = &mut player.stats;
+    match *event {
+        GameEvent::LocationEntered(Location::Wilds) => stats.wilds_explored += 1,
+        GameEvent::LocationEntered(Location::Village) => stats.village_visits += 1,
+        GameEvent::EnemyDefeated(_) => stats.battles_won += 1,
+        GameEvent::BattleFled => stats.battles_fled += 1,
+        GameEvent::DamageDealt(damage) => stats.damage_dealt += damage.max(0) as u32,
+        GameEvent::DamageTaken(damage) => stats.damage_taken += damage.max(0) as u32,
+        GameEvent::GoldChanged(amount) if amount >= 0 => stats.gold_earned += amount as u32,
+        GameEvent::GoldChanged(amount) => stats.gold_spent += amount.unsigned_abs(),
+        // Pills are swallowed as soon as they are found
+        GameEvent::ItemAcquired { item: Item::QiPill, .. } => stats.pills_used += 1,
+        GameEvent::QiTrained(qi) => stats.qi_trained += qi.max(0) as u32,
+        GameEvent::BreakthroughAttempted => stats.breakthroughs_attempted += 1,
+        GameEvent::BreakthroughFailed => stats.breakthroughs_failed += 1,
+        _ => {}
+    }
+}
+
+// A journey summed up, for the stats screen and the end-of-run report
+pub struct Report {
+    pub name: String,
+    pub class: String,
+    pub level: u32,
+    pub difficulty: String,
+    pub hardcore: bool,
+    pub outcome: String,
+    pub days: u32,
+    pub enemies_defeated: u32,
+    pub stats: Stats,
+}
+
+impl Report {
+    pub fn new(player: &Player, outcome: &str) -> Report {
+        let rules = difficulty::current();
+        Report {
+            name: player.name.clone(),
+            class: player.class_type.name().to_string(),
+            level: player.cultivation_level,
+            difficulty: rules.name,
+            hardcore: rules.hardcore,
+            outcome: outcome.to_string(),
+            days: player.days,
+            enemies_defeated: player.enemies_defeated,
+            stats: player.stats.clone(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{} the {}\nCultivation level {} on {} difficulty{}\n{}\n\nDays: {}\nEnemies defeated: {}\n",
+            self.name,
+            self.class,
+            self.level,
+            self.difficulty,
+            if self.hardcore { ", hardcore" } else { "" },
+            self.outcome,
+            self.days,
+            self.enemies_defeated
+        );
+        for (_, label, value) in self.stats.fields() {
+            text.push_str(&format!("{}: {}\n", label, value));
+        }
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        let stats: Vec<String> = self
+            .stats
+            .fields()
+            .iter()
+            .map(|(key, _, value)| format!("    \"{}\": {}", key, value))
+            .collect();
+        format!(
+            "{{\n  \"name\": {},\n  \"class\": {},\n  \"level\": {},\n  \"difficulty\": {},\n  \"hardcore\": {},\n  \"outcome\": {},\n  \"days\": {},\n  \"enemies_defeated\": {},\n  \"stats\": {{\n{}\n  }}\n}}\n",
+            json_string(&self.name),
+            json_string(&self.class),
+            self.level,
+            json_string(&self.difficulty),
+            self.hardcore,
+            json_string(&self.outcome),
+            self.days,
+            self.enemies_defeated,
+            stats.join(",\n")
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+// Writes the report to the reports folder, named after the character and the
+// time so earlier reports are never overwritten
+pub fn export(report: &Report, format: Format) -> io::Result<PathBuf> {
+    let (contents, extension) = match format {
+        Format::Text => (report.to_text(), "txt"),
+        Format::Json => (report.to_json(), "json"),
+    };
+    let name: String = report
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    fs::create_dir_all(REPORTS_DIR)?;
+    let path = PathBuf::from(REPORTS_DIR).join(format!("{}-{}.{}", name, seconds, extension));
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// Shows the report with options to export it, until the player picks the last option
+pub fn view(renderer: &mut Renderer, title: &str, report: &Report, done: &str) -> crossterm::Result<()> {
+    let mut question = title.to_string();
+    let mut selected = 0;
+    loop {
+        clear_screen(renderer)?;
+        display_question(renderer, &question)?;
+        let mut lines: Vec<Line> = Vec::new();
+        for (index, line) in report.to_text().lines().enumerate() {
+            // The character's name stands out at the top
+            lines.push(if index == 0 { Line::colored(line, theme::color(Role::Title)) } else { Line::plain(line) });
+        }
+        layout::show(renderer, Region::Body, lines)?;
+
+        let format = match select_menu(renderer, Decision::Leave, &["Export as text", "Export as JSON", done], &mut selected)? {
+            Some(0) => Format::Text,
+            Some(1) => Format::Json,
+            _ => return Ok(()),
+        };
+        question = match export(report, format) {
+            Ok(path) => format!("Report saved to {}", path.display()),
+            Err(error) => format!("The report could not be saved: {}", error),
+        };
+    }
+}
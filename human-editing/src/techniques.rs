@@ -0,0 +1,164 @@
+use rand::Rng;
+
+use crate::element::Element;
+use crate::{random, ClassType, Player};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TechniqueKind {
+    Cultivation, // Raises cultivation speed
+    Combat,      // Adds a skill to battles
+    Movement,    // Raises evasion and the chance to flee
+}
+
+#[derive(Debug)]
+pub struct Technique {
+    pub name: &'static str,
+    pub kind: TechniqueKind,
+    pub affinity: ClassType, // The class that comprehends this technique fastest
+    pub price: i32,
+    // Meaning depends on the kind: cultivation speed bonus in percent,
+    // skill damage, or evasion chance in percent
+    pub power: i32,
+    pub qi_cost: i32, // Only used by combat techniques
+    pub element: Option<Element>, // Combat techniques without one channel the player's spiritual root
+}
+
+#[derive(Debug)]
+pub struct LearnedTechnique {
+    pub technique: &'static Technique,
+    pub comprehension: u32, // 0 to 100, how much of the manual the player has grasped
+}
+
+pub const CATALOG: [Technique; 11] = [
+    Technique { name: "Iron Marrow Sutra", kind: TechniqueKind::Cultivation, affinity: ClassType::MartialArtist, price: 45, power: 20, qi_cost: 0, element: None },
+    Technique { name: "Nine Yang Breathing Method", kind: TechniqueKind::Cultivation, affinity: ClassType::QiCultivator, price: 60, power: 35, qi_cost: 0, element: None },
+    Technique { name: "Shadow Breath Scripture", kind: TechniqueKind::Cultivation, affinity: ClassType::Assassin, price: 45, power: 20, qi_cost: 0, element: None },
+    Technique { name: "Crushing Mountain Fist", kind: TechniqueKind::Combat, affinity: ClassType::MartialArtist, price: 50, power: 25, qi_cost: 15, element: Some(Element::Earth) },
+    Technique { name: "Azure Dragon Palm", kind: TechniqueKind::Combat, affinity: ClassType::QiCultivator, price: 55, power: 35, qi_cost: 25, element: Some(Element::Wood) },
+    Technique { name: "Thousand Cuts Blade", kind: TechniqueKind::Combat, affinity: ClassType::Assassin, price: 50, power: 28, qi_cost: 15, element: Some(Element::Metal) },
+    Technique { name: "Heaven Splitting Thunder", kind: TechniqueKind::Combat, affinity: ClassType::QiCultivator, price: 70, power: 40, qi_cost: 30, element: Some(Element::Lightning) },
+    Technique { name: "Frost Lotus Needles", kind: TechniqueKind::Combat, affinity: ClassType::Assassin, price: 60, power: 30, qi_cost: 20, element: Some(Element::Ice) },
+    Technique { name: "Stone Ape Leap", kind: TechniqueKind::Movement, affinity: ClassType::MartialArtist, price: 35, power: 8, qi_cost: 0, element: None },
+    Technique { name: "Drifting Cloud Steps", kind: TechniqueKind::Movement, affinity: ClassType::QiCultivator, price: 40, power: 10, qi_cost: 0, element: None },
+    Technique { name: "Swallow Skims Water", kind: TechniqueKind::Movement, affinity: ClassType::Assassin, price: 40, power: 15, qi_cost: 0, element: None },
+];
+
+// Comprehension a freshly opened manual starts with
+const STARTING_COMPREHENSION: u32 = 10;
+const BASE_FLEE_CHANCE: f64 = 0.4;
+
+pub fn kind_name(kind: TechniqueKind) -> &'static str {
+    match kind {
+        TechniqueKind::Cultivation => "Cultivation method",
+        TechniqueKind::Combat => "Combat technique",
+        TechniqueKind::Movement => "Movement technique",
+    }
+}
+
+pub fn knows(player: &Player, technique: &Technique) -> bool {
+    player.techniques.iter().any(|learned| learned.technique.name == technique.name)
+}
+
+pub fn learn(player: &mut Player, technique: &'static Technique) {
+    if !knows(player, technique) {
+        player.techniques.push(LearnedTechnique { technique, comprehension: STARTING_COMPREHENSION });
+    }
+}
+
+pub fn unlearned(player: &Player) -> Vec<&'static Technique> {
+    CATALOG.iter().filter(|technique| !knows(player, technique)).collect()
+}
+
+// A random manual the player doesn't know yet, for loot drops
+pub fn random_unlearned(player: &Player) -> Option<&'static Technique> {
+    let candidates = unlearned(player);
+    if candidates.is_empty() {
+        return None;
+    }
+    Some(candidates[random::rng().gen_range(0..candidates.len())])
+}
+
+// The cultivation method of the player's own class, handed out as quest rewards
+pub fn affinity_cultivation_method(player: &Player) -> Option<&'static Technique> {
+    CATALOG.iter().find(|technique| {
+        technique.kind == TechniqueKind::Cultivation
+            && technique.affinity == player.class_type
+            && !knows(player, technique)
+    })
+}
+
+// Players with the matching class grasp a technique far faster
+fn affinity_multiplier(player: &Player, technique: &Technique) -> f32 {
+    if technique.affinity == player.class_type {
+        1.5
+    } else {
+        0.75
+    }
+}
+
+// Progress comprehension of every learned technique of the given kind
+pub fn comprehend(player: &mut Player, kind: TechniqueKind, amount: u32) {
+    for index in 0..player.techniques.len() {
+        if player.techniques[index].technique.kind == kind {
+            comprehend_technique(player, index, amount);
+        }
+    }
+}
+
+pub fn comprehend_technique(player: &mut Player, index: usize, amount: u32) {
+    let multiplier = affinity_multiplier(player, player.techniques[index].technique);
+    let learned = &mut player.techniques[index];
+    let gained = (amount as f32 * multiplier).round() as u32;
+    learned.comprehension = (learned.comprehension + gained).min(100);
+}
+
+// A technique only gives its full effect once fully comprehended
+pub fn mastery(learned: &LearnedTechnique) -> f32 {
+    learned.comprehension as f32 / 100.0
+}
+
+// Cultivation speed including the bonus from every cultivation method
+pub fn effective_cultivation_speed(player: &Player) -> f32 {
+    let bonus: f32 = player
+        .techniques
+        .iter()
+        .filter(|learned| learned.technique.kind == TechniqueKind::Cultivation)
+        .map(|learned| learned.technique.power as f32 / 100.0 * mastery(learned))
+        .sum();
+    player.cultivation_speed + bonus
+}
+
+// Chance for an enemy attack to miss, from the best movement technique
+pub fn evasion_chance(player: &Player) -> f64 {
+    player
+        .techniques
+        .iter()
+        .filter(|learned| learned.technique.kind == TechniqueKind::Movement)
+        .map(|learned| learned.technique.power as f64 / 100.0 * mastery(learned) as f64)
+        .fold(0.0, f64::max)
+}
+
+pub fn flee_chance(player: &Player) -> f64 {
+    (BASE_FLEE_CHANCE + 2.0 * evasion_chance(player)).min(0.95)
+}
+
+// Indices into player.techniques of the skills usable in battle
+pub fn combat_skills(player: &Player) -> Vec<usize> {
+    (0..player.techniques.len())
+        .filter(|&index| player.techniques[index].technique.kind == TechniqueKind::Combat)
+        .collect()
+}
+
+pub fn skill_element(player: &Player, technique: &Technique) -> Element {
+    technique.element.unwrap_or(player.spiritual_root)
+}
+
+// Half power when first learned, full power once mastered. Techniques of the
+// player's own spiritual root flow more easily and hit harder.
+pub fn skill_damage(player: &Player, learned: &LearnedTechnique, enemy_defense: i32) -> i32 {
+    let mut power = learned.technique.power as f32 * (0.5 + 0.5 * mastery(learned));
+    if skill_element(player, learned.technique) == player.spiritual_root {
+        power *= 1.2;
+    }
+    (power as i32 + player.attack - enemy_defense).max(1)
+}
@@ -0,0 +1,108 @@
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::panic;
+use std::process;
+use crossterm::{
+    execute,
+    style::ResetColor,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers},
+};
+
+use crate::plain;
+use crate::renderer::Renderer;
+
+thread_local! {
+    static RAW_MODE_DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+// Keeps raw mode on while held and turns it off when dropped, so an error
+// returned halfway through a prompt doesn't leave the terminal raw. Guards can
+// nest, as when a prompt asks whether to quit: raw mode stays on until the
+// outermost one is dropped.
+pub struct RawMode {
+    terminal: bool,
+}
+
+impl RawMode {
+    pub fn enable(renderer: &Renderer) -> crossterm::Result<RawMode> {
+        // Raw mode belongs to a real terminal, there is none to switch when drawing elsewhere
+        let terminal = renderer.is_terminal();
+        if RAW_MODE_DEPTH.with(Cell::get) == 0 && terminal {
+            terminal::enable_raw_mode()?;
+        }
+        RAW_MODE_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        Ok(RawMode { terminal })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let depth = RAW_MODE_DEPTH.with(|depth| {
+            depth.set(depth.get() - 1);
+            depth.get()
+        });
+        if depth == 0 && self.terminal {
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+// Holds the terminal in game mode and puts it back when dropped, so returning
+// early with an error still leaves the player with a usable shell
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> crossterm::Result<Self> {
+        install_panic_hook();
+        // Plain mode leaves the terminal exactly as it is
+        if plain::enabled() {
+            return Ok(TerminalGuard);
+        }
+        // Mouse capture lets menu entries be clicked
+        execute!(io::stdout(), EnterAlternateScreen, cursor::Hide, EnableMouseCapture)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+// Undoes everything the game does to the terminal. Errors are ignored since this
+// runs while already shutting down.
+pub fn restore() {
+    if plain::enabled() {
+        return;
+    }
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(io::stdout(), DisableMouseCapture, ResetColor, cursor::Show, LeaveAlternateScreen);
+}
+
+// Restore the terminal before the panic message is printed, otherwise it is
+// written to the alternate screen and lost
+fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+}
+
+// Ends the game from anywhere, leaving a parting message on the normal screen
+pub fn exit(message: &str) -> ! {
+    restore();
+    if !message.is_empty() {
+        println!("{}", message);
+    }
+    let _ = io::stdout().flush();
+    process::exit(0);
+}
+
+// Ctrl+C arrives as an ordinary key press while in raw mode
+pub fn is_interrupt(key_event: &KeyEvent) -> bool {
+    key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL)
+}
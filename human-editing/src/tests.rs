@@ -0,0 +1,330 @@
+use std::cell::Cell;
+use std::fs;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use crate::autoplay::Decision;
+use crate::events::{self, GameEvent};
+use crate::harness::{assert_snapshot, Harness};
+use crate::menu::{MenuStack, QuitChoice, Screen};
+use crate::message_log::{self, ConsoleBuffer};
+use crate::quests::{self, Goal};
+use crate::{meditation, random, save};
+use crate::{
+    attempt_breakthrough, battle, buy_gear, buy_techniques, clear_screen, combat, data, difficulty, display_player_info, input, new_player, quit_options, read_text, select_menu,
+    select_option, select_yes_no, stats, talk_to_npc, ClassType, Element, Enemy, EnemyKind, Npc, Player, Quest, Ruleset,
+};
+
+fn player() -> Player {
+    let mut player = new_player(String::from("Lin"), ClassType::MartialArtist);
+    player.spiritual_root = Element::Metal;
+    player
+}
+
+fn demon_lord() -> Enemy {
+    Enemy {
+        name: String::from("Ancient Demon Lord"),
+        kind: EnemyKind::DemonLord,
+        health: 1000,
+        attack: 60,
+        defense: 35,
+        is_boss: true,
+        element: Element::Fire,
+        resistances: vec![Element::Fire, Element::Metal],
+    }
+}
+
+#[test]
+fn arrow_keys_move_through_the_menu() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Down, KeyCode::Down, KeyCode::Up, KeyCode::Enter]);
+    assert_eq!(select_option(&mut harness.renderer, Decision::Leave, &["Attack", "Use Qi", "Defend"]).unwrap(), 1);
+    assert!(harness.screen().contains("● 2. Use Qi"));
+}
+
+#[test]
+fn the_menu_wraps_around_at_the_top() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Up, KeyCode::Enter]);
+    assert_eq!(select_option(&mut harness.renderer, Decision::Leave, &["Attack", "Use Qi", "Defend"]).unwrap(), 2);
+}
+
+#[test]
+fn number_keys_pick_an_option_directly() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Char('3')]);
+    assert_eq!(select_option(&mut harness.renderer, Decision::Leave, &["Attack", "Use Qi", "Defend"]).unwrap(), 2);
+}
+
+#[test]
+fn escape_backs_out_of_a_submenu_and_keeps_the_selection() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Down, KeyCode::Esc]);
+    let mut selected = 0;
+    assert_eq!(select_menu(&mut harness.renderer, Decision::Leave, &["Buy gear", "Buy techniques"], &mut selected).unwrap(), None);
+    assert_eq!(selected, 1);
+}
+
+#[test]
+fn yes_no_answers_with_the_highlighted_choice() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Right, KeyCode::Enter]);
+    assert!(!select_yes_no(&mut harness.renderer, Decision::Leave, "Proceed?").unwrap());
+    harness.keys(&[KeyCode::Char('1')]);
+    assert!(select_yes_no(&mut harness.renderer, Decision::Leave, "Proceed?").unwrap());
+}
+
+#[test]
+fn typed_text_can_be_corrected_with_backspace() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Char('L'), KeyCode::Char('u'), KeyCode::Backspace, KeyCode::Char('i'), KeyCode::Char('n'), KeyCode::Enter]);
+    assert_eq!(read_text(&mut harness.renderer, Decision::Name, "Enter your name: ").unwrap(), "Lin");
+}
+
+#[test]
+fn trailing_spaces_are_dropped_from_typed_text() {
+    let mut harness = Harness::new();
+    harness.typed("Lin  ");
+    assert_eq!(read_text(&mut harness.renderer, Decision::Name, "Enter your name: ").unwrap(), "Lin");
+}
+
+#[test]
+fn running_out_of_keys_is_an_error() {
+    let mut harness = Harness::new();
+    assert!(select_option(&mut harness.renderer, Decision::Leave, &["Attack", "Use Qi", "Defend"]).is_err());
+}
+
+#[test]
+fn player_info_screen() {
+    let mut harness = Harness::new();
+    clear_screen(&mut harness.renderer).unwrap();
+    display_player_info(&mut harness.renderer, &player()).unwrap();
+    assert_snapshot("player_info", &harness.screen());
+}
+
+#[test]
+fn battle_screen_after_the_first_exchange() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Enter]);
+    let (mut player, mut enemy) = (player(), demon_lord());
+    // The fight waits for a second action the script doesn't have
+    assert!(battle(&mut harness.renderer, &mut player, &mut enemy, &mut ConsoleBuffer::new()).is_err());
+    assert_snapshot("battle", &harness.screen());
+}
+
+#[test]
+fn breakthrough_prompt() {
+    let mut harness = Harness::new();
+    let mut player = player();
+    player.cultivation_level = 3;
+    clear_screen(&mut harness.renderer).unwrap();
+    display_player_info(&mut harness.renderer, &player).unwrap();
+    assert!(attempt_breakthrough(&mut harness.renderer, &mut player, &mut ConsoleBuffer::new()).is_err());
+    assert_snapshot("breakthrough_prompt", &harness.screen());
+}
+
+#[test]
+fn declining_a_breakthrough_leaves_the_player_as_they_were() {
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Char('2')]);
+    let mut player = player();
+    let message = attempt_breakthrough(&mut harness.renderer, &mut player, &mut ConsoleBuffer::new()).unwrap();
+    assert_eq!(message, "You chose not to attempt the breakthrough at this time.");
+    assert_eq!(player.cultivation_level, 5);
+    assert_eq!(player.stats.breakthroughs_attempted, 0);
+}
+
+#[test]
+fn redrawing_an_unchanged_screen_writes_nothing() {
+    let mut harness = Harness::new();
+    let player = player();
+    display_player_info(&mut harness.renderer, &player).unwrap();
+    harness.screen();
+    clear_screen(&mut harness.renderer).unwrap();
+    display_player_info(&mut harness.renderer, &player).unwrap();
+    assert_eq!(harness.written(), "");
+}
+
+#[test]
+fn a_redraw_only_writes_the_cells_that_changed() {
+    let mut harness = Harness::new();
+    let mut player = player();
+    display_player_info(&mut harness.renderer, &player).unwrap();
+    harness.screen();
+    player.gold = 75;
+    clear_screen(&mut harness.renderer).unwrap();
+    display_player_info(&mut harness.renderer, &player).unwrap();
+    let written = harness.written();
+    assert!(written.contains("75"));
+    assert!(!written.contains("Health"));
+    // Nothing is wiped first, so there is no blank screen to flicker through
+    assert!(!written.contains("\x1b[2J"));
+}
+
+#[test]
+fn defeated_bandits_count_towards_the_elders_quest() {
+    for subscriber in [message_log::on_event, stats::on_event, quests::on_event] {
+        events::subscribe(subscriber);
+    }
+    let (mut player, mut log) = (player(), ConsoleBuffer::new());
+    let bandit = Enemy { name: String::from("Bandit"), kind: EnemyKind::Bandit, is_boss: false, ..demon_lord() };
+    let goal = Goal::DefeatBandits(3);
+    for _ in 0..3 {
+        assert!(!goal.fulfilled(&player));
+        combat::victory(&mut player, &bandit, &mut log);
+    }
+    assert!(goal.fulfilled(&player));
+    assert_eq!(player.stats.battles_won, 3);
+    assert_eq!(player.stats.gold_earned, 60);
+
+    // Other foes don't count, and spending is kept apart from earning
+    combat::victory(&mut player, &Enemy { kind: EnemyKind::RogueCultivator, ..bandit }, &mut log);
+    events::publish(&mut player, &mut log, GameEvent::GoldChanged(-15));
+    assert_eq!(player.bandits_defeated, 3);
+    assert_eq!((player.stats.gold_earned, player.stats.gold_spent), (80, 15));
+}
+
+#[test]
+fn the_bindings_file_is_only_written_when_asked_for() {
+    let directory = std::env::temp_dir().join(format!("ai-rpg-bindings-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    data::set_dir(directory.clone());
+
+    input::load();
+    assert!(!input::bindings_path().exists());
+    let path = input::write_defaults().unwrap();
+    let written = fs::read_to_string(&path);
+    fs::remove_dir_all(&directory).unwrap();
+    assert_eq!(path, directory.join("keybindings.cfg"));
+    assert!(written.unwrap().contains("confirm = enter, space"));
+}
+
+#[test]
+fn difficulties_are_only_read_from_the_rulesets_folder() {
+    let directory = std::env::temp_dir().join(format!("ai-rpg-rulesets-{}", std::process::id()));
+    fs::create_dir_all(directory.join("data").join("rulesets")).unwrap();
+    fs::write(directory.join("data").join("rulesets").join("gentle.rules"), "enemy_attack = 0.5\n").unwrap();
+    fs::write(directory.join("data").join("rulesets").join("Masochist.rules"), "ironman = true\n").unwrap();
+    fs::write(directory.join("outside.rules"), "enemy_attack = 0.1\n").unwrap();
+    data::set_dir(directory.join("data"));
+
+    let gentle = difficulty::find("gentle");
+    let masochist = difficulty::find("Masochist");
+    let outside = difficulty::find("../../outside");
+    fs::remove_dir_all(&directory).unwrap();
+    assert_eq!(gentle.map(|ruleset| ruleset.enemy_attack), Some(0.5));
+    // Ironman saves are deleted on death like hardcore ones
+    assert_eq!(masochist.map(|ruleset| (ruleset.name, ruleset.hardcore)), Some((String::from("Masochist"), true)));
+    assert!(outside.is_none());
+}
+
+#[test]
+fn a_hardcore_journey_cant_be_quit_without_saving() {
+    difficulty::set(difficulty::find("normal").unwrap());
+    assert!(quit_options(true).contains(&QuitChoice::Quit));
+
+    // Leaving without saving would keep the older save to load after a death
+    difficulty::set(Ruleset { hardcore: true, ..difficulty::find("normal").unwrap() });
+    assert_eq!(quit_options(true), [QuitChoice::KeepPlaying, QuitChoice::SaveAndQuit]);
+}
+
+fn bandit_hunt(bandits: u32) -> Npc {
+    Npc {
+        name: String::from("Wise Elder"),
+        quest: Quest { description: String::new(), goal: Goal::DefeatBandits(bandits), reward: 100, completed: false },
+    }
+}
+
+#[test]
+fn completed_quests_count_the_ones_still_open() {
+    thread_local! {
+        static REMAINING: Cell<Option<usize>> = const { Cell::new(None) };
+    }
+    events::subscribe(|_, _, event| {
+        if let GameEvent::QuestCompleted { remaining } = event {
+            REMAINING.with(|last| last.set(Some(*remaining)));
+        }
+    });
+    let (mut player, mut log) = (player(), ConsoleBuffer::new());
+    let mut npcs = vec![bandit_hunt(3), bandit_hunt(1)];
+    player.bandits_defeated = 1;
+
+    talk_to_npc(&mut player, &mut npcs, 1, &mut log).unwrap();
+    assert_eq!(REMAINING.with(Cell::get), Some(1));
+    player.bandits_defeated = 3;
+    talk_to_npc(&mut player, &mut npcs, 0, &mut log).unwrap();
+    assert_eq!(REMAINING.with(Cell::get), Some(0));
+}
+
+#[test]
+fn quitting_mid_battle_in_hardcore_keeps_the_wounds() {
+    let directory = std::env::temp_dir().join(format!("ai-rpg-saves-{}", std::process::id()));
+    save::use_dir(directory.clone());
+    difficulty::set(Ruleset { hardcore: true, ..difficulty::find("normal").unwrap() });
+    random::seed(1);
+    let (mut player, mut log) = (player(), ConsoleBuffer::new());
+    let mut npcs = vec![bandit_hunt(1)];
+    let mut bandit = Enemy {
+        name: String::from("Bandit"),
+        kind: EnemyKind::Bandit,
+        health: 500,
+        attack: 30,
+        defense: 0,
+        is_boss: false,
+        element: Element::Metal,
+        resistances: Vec::new(),
+    };
+    save::checkpoint(&player, &npcs).unwrap();
+
+    // The keys run out on the third round, as if the game were quit or killed there
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Enter, KeyCode::Enter]);
+    assert!(battle(&mut harness.renderer, &mut player, &mut bandit, &mut log).is_err());
+    let saved = save::load(save::current_slot(), &mut npcs);
+    fs::remove_dir_all(&directory).unwrap();
+    let saved = saved.unwrap();
+    assert!(player.health < player.max_health);
+    assert_eq!(saved.health, player.health);
+}
+
+#[test]
+fn ctrl_c_while_searching_the_log_asks_to_quit() {
+    let mut harness = Harness::new();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+    harness.events([
+        key(KeyCode::Char('/')),
+        Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        key(KeyCode::Enter), // Keep playing
+        key(KeyCode::Char('x')),
+    ]);
+    assert!(message_log::view_history(&mut harness.renderer, &ConsoleBuffer::new()).is_err());
+    assert!(harness.screen().contains("Search: x_"));
+}
+
+#[test]
+fn purchases_that_cant_be_paid_for_take_no_time() {
+    let (mut player, mut log) = (player(), ConsoleBuffer::new());
+    player.gold = 0;
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Enter]);
+    assert_eq!(buy_gear(&mut harness.renderer, &mut player, &mut MenuStack::new(Screen::GearShop), &mut log).unwrap(), None);
+    harness.keys(&[KeyCode::Enter]);
+    assert_eq!(buy_techniques(&mut harness.renderer, &mut player, &mut MenuStack::new(Screen::TechniqueShop), &mut log).unwrap(), None);
+    assert_eq!(player.gold, 0);
+}
+
+#[test]
+fn meditation_follows_the_remapped_keys() {
+    let directory = std::env::temp_dir().join(format!("ai-rpg-meditation-keys-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("keybindings.cfg"), "up = w\nconfirm = q\n").unwrap();
+    data::set_dir(directory.clone());
+    input::load();
+    fs::remove_dir_all(&directory).unwrap();
+
+    let mut player = player();
+    let mut harness = Harness::new();
+    harness.keys(&[KeyCode::Char('w'), KeyCode::Char('q')]);
+    let result = meditation::meditate(&mut harness.renderer, &mut player);
+    input::load();
+    result.unwrap();
+}
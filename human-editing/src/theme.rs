@@ -0,0 +1,347 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use crossterm::style::Color;
+
+use crate::data::{self, Problem};
+
+const THEMES_DIR: &str = "themes";
+const PALETTE_EXTENSION: &str = "palette";
+
+// What a color is used for. Everything the game draws in color asks the active
+// theme for one of these instead of naming a color itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Title,
+    Question,
+    Highlight,
+    PlayerBorder,
+    PlayerBackground,
+    EnemyBorder,
+    EnemyBackground,
+    Attack,
+    Defense,
+    Meridian,
+    Level,
+    Gold,
+    Root,
+    HealthHigh,
+    HealthMid,
+    HealthLow,
+    QiHigh,
+    QiMid,
+    QiLow,
+    Progress,
+    ProgressDone,
+    Combat,
+    Loot,
+    Quest,
+    System,
+    Lightning,
+    Input,
+}
+
+// Roles in palette order, by their name in palette files
+const ROLES: [(&str, Role); 27] = [
+    ("title", Role::Title),
+    ("question", Role::Question),
+    ("highlight", Role::Highlight),
+    ("player_border", Role::PlayerBorder),
+    ("player_background", Role::PlayerBackground),
+    ("enemy_border", Role::EnemyBorder),
+    ("enemy_background", Role::EnemyBackground),
+    ("attack", Role::Attack),
+    ("defense", Role::Defense),
+    ("meridian", Role::Meridian),
+    ("level", Role::Level),
+    ("gold", Role::Gold),
+    ("root", Role::Root),
+    ("health_high", Role::HealthHigh),
+    ("health_mid", Role::HealthMid),
+    ("health_low", Role::HealthLow),
+    ("qi_high", Role::QiHigh),
+    ("qi_mid", Role::QiMid),
+    ("qi_low", Role::QiLow),
+    ("progress", Role::Progress),
+    ("progress_done", Role::ProgressDone),
+    ("combat", Role::Combat),
+    ("loot", Role::Loot),
+    ("quest", Role::Quest),
+    ("system", Role::System),
+    ("lightning", Role::Lightning),
+    ("input", Role::Input),
+];
+
+const fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+// The original look of the game
+const CLASSIC: [Color; 27] = [
+    Color::Green, Color::Yellow, rgb(112, 154, 255),
+    Color::Blue, rgb(6, 6, 51), Color::Red, rgb(51, 6, 6),
+    rgb(212, 99, 99), rgb(155, 155, 185), rgb(214, 138, 214), rgb(250, 161, 157), Color::Yellow, rgb(120, 200, 230),
+    rgb(110, 210, 110), rgb(230, 200, 80), rgb(230, 80, 80),
+    rgb(172, 232, 137), rgb(120, 170, 100), rgb(80, 110, 70),
+    rgb(112, 154, 255), rgb(240, 200, 80),
+    rgb(230, 120, 120), rgb(240, 200, 80), rgb(120, 200, 230), rgb(200, 200, 200),
+    rgb(180, 160, 255), Color::Yellow,
+];
+
+// Pure bright colors on black for low vision and washed out screens
+const HIGH_CONTRAST: [Color; 27] = [
+    rgb(255, 255, 255), rgb(255, 255, 0), rgb(0, 255, 255),
+    rgb(255, 255, 255), rgb(0, 0, 0), rgb(255, 80, 80), rgb(0, 0, 0),
+    rgb(255, 255, 255), rgb(255, 255, 255), rgb(255, 255, 255), rgb(255, 255, 255), rgb(255, 255, 0), rgb(0, 255, 255),
+    rgb(0, 255, 0), rgb(255, 255, 0), rgb(255, 0, 0),
+    rgb(0, 255, 255), rgb(0, 200, 200), rgb(0, 150, 150),
+    rgb(0, 255, 255), rgb(255, 255, 0),
+    rgb(255, 120, 120), rgb(255, 255, 0), rgb(0, 255, 255), rgb(255, 255, 255),
+    rgb(255, 0, 255), rgb(255, 255, 0),
+];
+
+// The Okabe-Ito palette, which stays distinguishable with every common kind of
+// color blindness. Health runs blue to orange to vermillion instead of green to red.
+const COLORBLIND: [Color; 27] = [
+    rgb(0, 158, 115), rgb(240, 228, 66), rgb(86, 180, 233),
+    rgb(0, 114, 178), rgb(6, 6, 51), rgb(213, 94, 0), rgb(40, 20, 0),
+    rgb(213, 94, 0), rgb(200, 200, 200), rgb(204, 121, 167), rgb(230, 159, 0), rgb(240, 228, 66), rgb(86, 180, 233),
+    rgb(86, 180, 233), rgb(230, 159, 0), rgb(213, 94, 0),
+    rgb(0, 158, 115), rgb(0, 120, 90), rgb(0, 80, 60),
+    rgb(86, 180, 233), rgb(240, 228, 66),
+    rgb(213, 94, 0), rgb(240, 228, 66), rgb(86, 180, 233), rgb(200, 200, 200),
+    rgb(204, 121, 167), rgb(240, 228, 66),
+];
+
+const BUILT_IN: [(&str, [Color; 27]); 3] = [
+    ("classic", CLASSIC),
+    ("high-contrast", HIGH_CONTRAST),
+    ("colorblind", COLORBLIND),
+];
+
+// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    NoColor,
+}
+
+impl ColorMode {
+    pub fn parse(name: &str) -> Option<Option<ColorMode>> {
+        match name {
+            "auto" => Some(None),
+            "truecolor" => Some(Some(ColorMode::TrueColor)),
+            "256" => Some(Some(ColorMode::Ansi256)),
+            "16" => Some(Some(ColorMode::Ansi16)),
+            "none" => Some(Some(ColorMode::NoColor)),
+            _ => None,
+        }
+    }
+
+    pub fn name(mode: Option<ColorMode>) -> &'static str {
+        match mode {
+            None => "auto",
+            Some(ColorMode::TrueColor) => "truecolor",
+            Some(ColorMode::Ansi256) => "256",
+            Some(ColorMode::Ansi16) => "16",
+            Some(ColorMode::NoColor) => "none",
+        }
+    }
+}
+
+struct Active {
+    colors: [Color; 27],
+    mode: ColorMode,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Active> = const { RefCell::new(Active { colors: CLASSIC, mode: ColorMode::TrueColor }) };
+}
+
+pub fn color(role: Role) -> Color {
+    let index = ROLES.iter().position(|(_, known)| *known == role).unwrap_or(0);
+    ACTIVE.with(|active| active.borrow().colors[index])
+}
+
+// Switches to a built-in theme or a palette from the themes folder. Unknown
+// names fall back to the classic theme.
+pub fn apply(name: &str, mode: Option<ColorMode>) {
+    let colors = match BUILT_IN.iter().find(|(built_in, _)| *built_in == name) {
+        Some((_, colors)) => *colors,
+        None => load_palette(name).unwrap_or(CLASSIC),
+    };
+    let mode = mode.unwrap_or_else(detect_mode);
+    ACTIVE.with(|active| *active.borrow_mut() = Active { colors, mode });
+}
+
+// NO_COLOR wins over everything, then COLORTERM and TERM say what the terminal supports
+fn detect_mode() -> ColorMode {
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return ColorMode::NoColor;
+    }
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+        return ColorMode::TrueColor;
+    }
+    if env::var("TERM").unwrap_or_default().contains("256color") {
+        return ColorMode::Ansi256;
+    }
+    ColorMode::Ansi16
+}
+
+// Every theme the player can pick, built-ins first
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = BUILT_IN.iter().map(|(name, _)| name.to_string()).collect();
+    if let Ok(entries) = fs::read_dir(data::path(THEMES_DIR)) {
+        let mut custom: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == PALETTE_EXTENSION))
+            .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .collect();
+        custom.sort();
+        names.extend(custom);
+    }
+    names
+}
+
+// Palette files are `role = color` lines on top of an optional `base = <built-in theme>`.
+// Colors are #rrggbb or a basic color name. Unknown roles and bad colors are skipped.
+fn load_palette(name: &str) -> Option<[Color; 27]> {
+    let contents = fs::read_to_string(palette_path(name)).ok()?;
+    Some(parse_palette(&contents).0)
+}
+
+fn palette_path(name: &str) -> PathBuf {
+    data::path(THEMES_DIR).join(format!("{}.{}", name, PALETTE_EXTENSION))
+}
+
+// The palette's colors, and a description of each line that had to be skipped
+fn parse_palette(contents: &str) -> ([Color; 27], Vec<String>) {
+    let mut colors = CLASSIC;
+    let mut skipped = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => {
+                skipped.push(format!("line {}: expected `role = color`", number + 1));
+                continue;
+            }
+        };
+        if key == "base" {
+            match BUILT_IN.iter().find(|(built_in, _)| *built_in == value) {
+                Some((_, base)) => colors = *base,
+                None => skipped.push(format!("line {}: no built-in theme called `{}`", number + 1, value)),
+            }
+        } else if let Some(index) = ROLES.iter().position(|(role, _)| *role == key) {
+            match parse_color(value) {
+                Some(color) => colors[index] = color,
+                None => skipped.push(format!("line {}: `{}` isn't a color", number + 1, value)),
+            }
+        } else {
+            skipped.push(format!("line {}: unknown role `{}`", number + 1, key));
+        }
+    }
+    (colors, skipped)
+}
+
+// Checks every palette in the themes folder, reporting the lines the game would skip
+pub fn validate() -> (usize, Vec<Problem>) {
+    let mut checked = 0;
+    let mut problems = Vec::new();
+    for name in available().iter().skip(BUILT_IN.len()) {
+        let path = palette_path(name);
+        checked += 1;
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                for skipped in parse_palette(&contents).1 {
+                    problems.push(Problem::warning(path.clone(), skipped));
+                }
+            }
+            Err(error) => problems.push(Problem::error(path, format!("can't be read ({})", error))),
+        }
+    }
+    (checked, problems)
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    let color = match value.to_lowercase().as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+    Some(color)
+}
+
+// Turns a theme color into one the terminal can show, or None to leave the default
+pub fn adapt(color: Color) -> Option<Color> {
+    let mode = ACTIVE.with(|active| active.borrow().mode);
+    match (mode, color) {
+        (ColorMode::NoColor, _) => None,
+        (ColorMode::Ansi256, Color::Rgb { r, g, b }) => Some(Color::AnsiValue(ansi256(r, g, b))),
+        (ColorMode::Ansi16, Color::Rgb { r, g, b }) => Some(nearest_ansi16(r, g, b)),
+        _ => Some(color),
+    }
+}
+
+// Nearest entry in the 6x6x6 color cube or the grey ramp of the 256 color palette
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            _ => 232 + ((r - 8) / 10).min(23),
+        };
+    }
+    let level = |channel: u8| (channel as u16 * 5 + 127) / 255;
+    (16 + 36 * level(r) + 6 * level(g) + level(b)) as u8
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    // Typical xterm values for the 16 basic colors
+    const BASIC: [(Color, (i32, i32, i32)); 16] = [
+        (Color::Black, (0, 0, 0)),
+        (Color::DarkRed, (205, 0, 0)),
+        (Color::DarkGreen, (0, 205, 0)),
+        (Color::DarkYellow, (205, 205, 0)),
+        (Color::DarkBlue, (0, 0, 238)),
+        (Color::DarkMagenta, (205, 0, 205)),
+        (Color::DarkCyan, (0, 205, 205)),
+        (Color::Grey, (229, 229, 229)),
+        (Color::DarkGrey, (127, 127, 127)),
+        (Color::Red, (255, 0, 0)),
+        (Color::Green, (0, 255, 0)),
+        (Color::Yellow, (255, 255, 0)),
+        (Color::Blue, (92, 92, 255)),
+        (Color::Magenta, (255, 0, 255)),
+        (Color::Cyan, (0, 255, 255)),
+        (Color::White, (255, 255, 255)),
+    ];
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    BASIC
+        .iter()
+        .min_by_key(|(_, (br, bg, bb))| (r - br).pow(2) + (g - bg).pow(2) + (b - bb).pow(2))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
@@ -0,0 +1,276 @@
+use rand::Rng;
+use std::time::{Duration, Instant};
+use crossterm::{
+    event::{Event},
+};
+
+use crate::art;
+use crate::input::{self, Action};
+use crate::layout::{self, Line, Region};
+use crate::renderer::Renderer;
+use crate::terminal::RawMode;
+use crate::theme::{self, Role};
+use crate::autoplay::Decision;
+use crate::save;
+use crate::{clear_screen, confirm_quit, cultivation, difficulty, display_question, plain, random, select_option, ClassType, Player};
+
+pub enum TribulationOutcome {
+    Survived,
+    Abandoned { health_lost: i32, qi_lost: i32 },
+    Died { wave: u32 },
+}
+
+// How well the player timed a braced defense against a strike
+pub enum Timing {
+    Perfect,
+    Good,
+    Missed,
+}
+
+// How a lightning strike is met, short of abandoning the tribulation
+pub enum Defense {
+    Brace(Timing),
+    Channel, // Absorbs the strike with Qi
+    Talisman,
+}
+
+// The answers to a lightning wave, in the order they are offered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Response {
+    Brace,
+    Channel,
+    Talisman,
+    Abandon,
+}
+
+const RESPONSES: [Response; 4] = [Response::Brace, Response::Channel, Response::Talisman, Response::Abandon];
+
+const BAR_WIDTH: i32 = 31;
+const TIMING_WINDOW: Duration = Duration::from_secs(4);
+
+// Breaking into these levels calls down the heavens instead of a simple success roll
+pub fn is_major(target_level: u32) -> bool {
+    matches!(target_level, 3 | 5 | 6)
+}
+
+pub fn wave_count(target_level: u32) -> u32 {
+    match target_level {
+        3 => 3,
+        5 => 6,
+        _ => 9, // The nine heavenly thunders guarding immortality
+    }
+}
+
+// Every wave hits harder than the one before it, and as hard as the difficulty's enemies do
+pub fn strike_power(target_level: u32, wave: u32) -> i32 {
+    let power = (12.0 * target_level as f32 * (1.0 + 0.15 * (wave - 1) as f32)) as i32 + random::rng().gen_range(0..=5);
+    difficulty::scale(power, difficulty::current().enemy_attack)
+}
+
+// Meets a strike with a defense, taking whatever gets through. Returns what
+// happened, ending with the damage taken.
+pub fn withstand(player: &mut Player, strike: i32, defense: Defense) -> String {
+    let qi_cost = strike;
+    let (damage, message) = match defense {
+        Defense::Brace(timing) => {
+            let (multiplier, message) = match timing {
+                Timing::Perfect => (0.25, "A perfectly timed guard! The lightning glances off you"),
+                Timing::Good => (0.5, "You brace in time and weather most of the strike"),
+                Timing::Missed => (1.0, "You mistime your guard and the lightning hits you head on"),
+            };
+            ((strike as f32 * multiplier) as i32, message.to_string())
+        }
+        Defense::Channel => {
+            // Qi cultivators are far better at absorbing heavenly energy
+            let absorb_rate = match player.class_type {
+                ClassType::QiCultivator => 0.9,
+                _ => 0.7,
+            };
+            let spent = player.qi.min(qi_cost).max(0);
+            player.qi -= spent;
+            let absorbed = (strike as f32 * absorb_rate * spent as f32 / qi_cost as f32) as i32;
+            (strike - absorbed, format!("You channel {} Qi and absorb {} of the strike", spent, absorbed))
+        }
+        Defense::Talisman if player.talismans > 0 => {
+            player.talismans -= 1;
+            (0, "The talisman burns to ash and swallows the lightning whole".to_string())
+        }
+        Defense::Talisman => (strike, "You reach for a talisman but have none left!".to_string()),
+    };
+
+    // Martial artists have tempered their bodies against exactly this
+    let damage = match player.class_type {
+        ClassType::MartialArtist => damage * 4 / 5,
+        _ => damage,
+    };
+    player.health -= damage;
+    format!("{}, taking {} damage.", message, damage)
+}
+
+// Fleeing the heavens costs half of everything and tears the meridians
+pub fn abandon(player: &mut Player) -> TribulationOutcome {
+    let health_lost = player.health / 2;
+    let qi_lost = player.qi / 2;
+    player.health -= health_lost;
+    player.qi -= qi_lost;
+    cultivation::injure_meridians(player, 25);
+    TribulationOutcome::Abandoned { health_lost, qi_lost }
+}
+
+pub fn endure(renderer: &mut Renderer, player: &mut Player) -> crossterm::Result<TribulationOutcome> {
+    let target_level = player.cultivation_level + 1;
+    let waves = wave_count(target_level);
+    let mut last_strike = String::from("Dark clouds gather overhead...");
+
+    for wave in 1..=waves {
+        let strike = strike_power(target_level, wave);
+        let qi_cost = strike;
+        // Quitting between waves saves the burns taken so far
+        if let Err(error) = save::refresh(player) {
+            last_strike = format!("Saving failed: {}", error);
+        }
+
+        clear_screen(renderer)?;
+        display_question(renderer, &format!(
+            "Heavenly Tribulation - lightning wave {} of {}",
+            wave, waves
+        ))?;
+        // The storm overhead sits above the status, leaving room for the timing bar
+        let body = layout::rect(renderer, Region::Body)?;
+        let (width, height) = body.map_or((80, 0), |body| (body.width as usize, body.height as usize));
+        let lightning = theme::color(Role::Lightning);
+        let mut status = art::render(art::Kind::Event, "tribulation", width, height.saturating_sub(7), lightning);
+        status.extend(status_lines(player, strike, &last_strike));
+        layout::show(renderer, Region::Body, status.clone())?;
+
+        let options: Vec<String> = RESPONSES
+            .iter()
+            .map(|response| match response {
+                Response::Brace => "Brace and time your defense".to_string(),
+                Response::Channel => format!("Channel Qi to absorb the strike ({} Qi)", qi_cost),
+                Response::Talisman => format!("Burn a Thunder Ward talisman ({} left)", player.talismans),
+                Response::Abandon => "Abandon the tribulation".to_string(),
+            })
+            .collect();
+        let option_refs: Vec<&str> = options.iter().map(|option| option.as_str()).collect();
+        let decision = Decision::Tribulation { player, wave, waves, strike, responses: &RESPONSES };
+        let defense = match RESPONSES[select_option(renderer, decision, &option_refs)?] {
+            Response::Brace => Defense::Brace(time_defense(renderer, player, status)?),
+            Response::Channel => Defense::Channel,
+            Response::Talisman => Defense::Talisman,
+            Response::Abandon => return Ok(abandon(player)),
+        };
+        last_strike = withstand(player, strike, defense);
+
+        if player.health <= 0 {
+            return Ok(TribulationOutcome::Died { wave });
+        }
+    }
+
+    Ok(TribulationOutcome::Survived)
+}
+
+fn status_lines(player: &Player, strike: i32, last_strike: &str) -> Vec<Line> {
+    vec![
+        Line::plain(format!("Health: {}   Qi: {}   Talismans: {}", player.health, player.qi, player.talismans)),
+        Line::colored(format!("Incoming strike power: {}", strike), theme::color(Role::Lightning)),
+        Line::plain(last_strike),
+    ]
+}
+
+// Assassins have the sharpest reflexes and get a wider zone
+fn zone_radius(player: &Player) -> i32 {
+    match player.class_type {
+        ClassType::Assassin => 4,
+        _ => 2,
+    }
+}
+
+// Without the sweeping bar the guard is left to chance, at the odds of a press
+// landing on a random spot of the bar
+pub fn roll_timing(player: &Player) -> Timing {
+    let roll: f64 = random::rng().gen();
+    let perfect = 1.0 / BAR_WIDTH as f64;
+    let good = (2 * zone_radius(player) + 1) as f64 / BAR_WIDTH as f64;
+    if roll < perfect {
+        Timing::Perfect
+    } else if roll < good {
+        Timing::Good
+    } else {
+        Timing::Missed
+    }
+}
+
+// A marker sweeps across the bar; pressing Space or Enter inside the zone softens the strike
+fn time_defense(renderer: &mut Renderer, player: &Player, status: Vec<Line>) -> crossterm::Result<Timing> {
+    let zone_radius = zone_radius(player);
+    if plain::enabled() {
+        return Ok(roll_timing(player));
+    }
+
+    let center = BAR_WIDTH / 2;
+    let mut position: i32 = 0;
+    let mut direction: i32 = 1;
+    let mut started = Instant::now();
+
+    let raw_mode = RawMode::enable(renderer)?;
+    let timing = loop {
+        draw_timing_bar(renderer, &status, position, center, zone_radius)?;
+
+        if started.elapsed() >= TIMING_WINDOW {
+            break Timing::Missed;
+        }
+
+        if input::poll(renderer, Duration::from_millis(40))? {
+            let event = input::read(renderer)?;
+            match input::action(&event) {
+                Some(Action::Confirm) => {
+                    let distance = (position - center).abs();
+                    break if distance == 0 {
+                        Timing::Perfect
+                    } else if distance <= zone_radius {
+                        Timing::Good
+                    } else {
+                        Timing::Missed
+                    };
+                }
+                Some(Action::Quit) => {
+                    confirm_quit(renderer)?;
+                    // Time spent deciding not to quit doesn't count against the guard
+                    started = Instant::now();
+                }
+                _ if matches!(event, Event::Resize(_, _)) => layout::reflow(renderer)?,
+                _ => {}
+            }
+        }
+
+        // Bounce the marker off both ends of the bar
+        position += direction;
+        if position <= 0 || position >= BAR_WIDTH - 1 {
+            direction = -direction;
+        }
+    };
+    drop(raw_mode);
+
+    Ok(timing)
+}
+
+fn draw_timing_bar(renderer: &mut Renderer, status: &[Line], position: i32, center: i32, zone_radius: i32) -> crossterm::Result<()> {
+    let bar: String = (0..BAR_WIDTH)
+        .map(|i| {
+            if i == position {
+                '|'
+            } else if (i - center).abs() <= zone_radius {
+                '#'
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    let mut lines = status.to_vec();
+    lines.push(Line::default());
+    lines.push(Line::plain("Press Space when the marker is in the zone:"));
+    lines.push(Line::plain(format!("[{}]", bar)));
+    layout::show(renderer, Region::Body, lines)
+}
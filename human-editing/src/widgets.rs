@@ -0,0 +1,85 @@
+use crossterm::style::Color;
+
+use crate::layout::{Line, Span};
+use crate::theme::{self, Role};
+
+// Width of the label column in front of gauges
+const LABEL_WIDTH: usize = 7;
+
+// How a gauge picks its color from how full it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GaugeStyle {
+    Health,   // Green, turning yellow then red as it drains
+    Qi,       // Bright when full, dimming as it drains
+    Progress, // Fills up blue and turns gold once complete
+}
+
+impl GaugeStyle {
+    fn color(self, fraction: f32) -> Color {
+        let role = match self {
+            GaugeStyle::Health if fraction > 0.5 => Role::HealthHigh,
+            GaugeStyle::Health if fraction > 0.25 => Role::HealthMid,
+            GaugeStyle::Health => Role::HealthLow,
+            GaugeStyle::Qi if fraction > 0.5 => Role::QiHigh,
+            GaugeStyle::Qi if fraction > 0.25 => Role::QiMid,
+            GaugeStyle::Qi => Role::QiLow,
+            GaugeStyle::Progress if fraction >= 1.0 => Role::ProgressDone,
+            GaugeStyle::Progress => Role::Progress,
+        };
+        theme::color(role)
+    }
+}
+
+// A labelled bar like `Health [██████░░░░] 84/120`, exactly `width` columns wide
+pub fn gauge(label: &str, current: i32, max: i32, width: usize, style: GaugeStyle, label_color: Color) -> Line {
+    let fraction = if max > 0 { (current.max(0) as f32 / max as f32).min(1.0) } else { 0.0 };
+    let numbers = format!(" {}/{}", current.max(0), max);
+    let bar_width = width.saturating_sub(LABEL_WIDTH + numbers.len() + 2);
+    let filled = (fraction * bar_width as f32).round() as usize;
+    let color = style.color(fraction);
+
+    Line::new(vec![
+        Span::colored(format!("{:<1$}", label, LABEL_WIDTH), label_color),
+        Span::colored("[", label_color),
+        Span::colored("█".repeat(filled), color),
+        Span::colored("░".repeat(bar_width - filled), color),
+        Span::colored("]", label_color),
+        Span::colored(numbers, color),
+    ])
+}
+
+// Draws a box around the rows with the title set into the top border. Rows are
+// cut or padded to fit and everything gets the panel's background.
+pub fn panel(title: &str, rows: Vec<Line>, width: usize, border: Color, background: Color) -> Vec<Line> {
+    let inner = width.saturating_sub(2);
+    let title: String = title.chars().take(inner.saturating_sub(2)).collect();
+    let top = if title.is_empty() {
+        format!("┌{}┐", "─".repeat(inner))
+    } else {
+        format!("┌ {} {}┐", title, "─".repeat(inner - title.chars().count() - 2))
+    };
+
+    let mut lines = vec![Line::new(vec![Span::colored(top, border).on(background)])];
+    for row in rows {
+        let mut spans = vec![Span::colored("│", border).on(background)];
+        let mut used = 0;
+        for span in row.spans {
+            let text: String = span.text.chars().take(inner - used).collect();
+            used += text.chars().count();
+            spans.push(Span { text, ..span }.on(background));
+        }
+        spans.push(Span::plain(" ".repeat(inner - used)).on(background));
+        spans.push(Span::colored("│", border).on(background));
+        lines.push(Line::new(spans));
+    }
+    lines.push(Line::new(vec![Span::colored(format!("└{}┘", "─".repeat(inner)), border).on(background)]));
+    lines
+}
+
+// A `label value` row for use inside a panel
+pub fn field(label: &str, value: impl Into<String>, label_color: Color, value_color: Color) -> Line {
+    Line::new(vec![
+        Span::colored(format!("{} ", label), label_color),
+        Span::colored(value, value_color),
+    ])
+}
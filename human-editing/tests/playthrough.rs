@@ -0,0 +1,23 @@
+use std::fs;
+use std::process::Command;
+
+// Lets the autoplay bot play whole journeys, from the intro to an ending, in a
+// scratch directory so no save, profile or settings file is left behind
+#[test]
+fn the_bot_plays_journeys_to_an_ending() {
+    for seed in [1, 2, 3] {
+        let directory = std::env::temp_dir().join(format!("ai-rpg-playthrough-{}-{}", std::process::id(), seed));
+        fs::create_dir_all(&directory).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_ai-rpg"))
+            .args(["new", "--autoplay", "--seed", &seed.to_string()])
+            .current_dir(&directory)
+            .output()
+            .unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let transcript = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "seed {} failed:\n{}", seed, transcript);
+        assert!(transcript.contains("Welcome, Bot!"), "seed {} never started:\n{}", seed, transcript);
+        assert!(transcript.contains("Your journey has ended."), "seed {} never ended:\n{}", seed, transcript);
+    }
+}